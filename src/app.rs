@@ -9,12 +9,15 @@ use std::{
         atomic::{AtomicUsize, Ordering},
     },
     thread::sleep,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 /// Global control to ensure Rayon is initialized at most once.
 static RAYON_INIT: Once = Once::new();
 
+/// Candidates probed per needed image when matching aspect ratios to monitor slots.
+const MATCH_POOL_FACTOR: usize = 3;
+
 #[cfg(target_env = "gnu")]
 unsafe extern "C" {
     /// Releases unused memory from the system allocator back to the operating system kernel.
@@ -65,10 +68,11 @@ unsafe extern "C" {
 /// This state allows the iterative runner to distinguish between a successfully applied background
 /// (which should pause for the configured sleep interval) and a self-healing restart (which should
 /// skip the delay and retry immediately).
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum CycleOutcome {
-    /// The wallpaper cycle succeeded. The execution runner should sleep for `interval` seconds.
-    Success,
+    /// The wallpaper cycle succeeded with the given images.
+    /// The execution runner should sleep for `interval` seconds.
+    Success(Vec<FileInfo>),
     /// A temporary error or history exhaustion occurred. The runner should retry immediately.
     Retry,
 }
@@ -89,11 +93,23 @@ pub fn run() -> WallSwitchResult<()> {
     // 2. Parse command-line arguments as the primary source of intent
     let args = Arguments::build();
 
+    // 2.1. Control client: forward the command to the running daemon and exit
+    if let Some(Command::Ctl { command }) = &args.command {
+        let reply = send_command(command, &env)?;
+        println!("{}", reply.strip_prefix("ok: ").unwrap_or(&reply));
+        return Ok(());
+    }
+
+    // 2.2. Standalone renderer: write the composed wallpaper to a file and exit
+    if let Some(Command::Render(render)) = &args.command {
+        return render_to_file(render, args.verbose, &env);
+    }
+
     // 3. Load persistent state (History and BLAKE3 hash cache) from disk
     let mut state = State::load(&env);
 
     // 4. Initialize configuration by merging JSON file settings with CLI overrides
-    let config = Config::new(&args, &env)?;
+    let mut config = Config::new(&args, &env)?;
 
     // 5. Handle configuration dump requests if the --config flag is present
     if args.config {
@@ -102,6 +118,12 @@ pub fn run() -> WallSwitchResult<()> {
         process::exit(0);
     }
 
+    // 5.1. Show the applied wallpaper sets
+    if args.history {
+        list_history(&state);
+        process::exit(0);
+    }
+
     // 6. Initialize the thread pool before executing heavy CPU operations
     init_rayon_thread_pool(config.max_threads_percent, config.verbose);
 
@@ -125,34 +147,397 @@ pub fn run() -> WallSwitchResult<()> {
         process::exit(0);
     }
 
+    // 7.1. Bring back an earlier wallpaper set
+    if let Some(back) = args.previous {
+        restore_previous(back, &config, &state, &env)?;
+        process::exit(0);
+    }
+
     // 8. Normal operation: Show startup info and clean up previous processes
     show_initial_msgs(&config, &env)?;
     kill_other_instances(&config, &env)?;
 
-    // 9. Execute either a single switch or start the infinite loop
-    if config.once {
+    // 8.1. Wait (up to 3s) for the compositor IPC at startup; cycles detect in a single pass
+    if (config.detect_resolution || config.desktop.is_wayland())
+        && let Err(err) = detect_monitors(&config)
+        && config.verbose
+    {
+        eprintln!("{err}\nUsing the configured monitor resolutions.");
+    }
+
+    // 9. Execute a single switch, the socket-controlled daemon, or the infinite loop
+    let mut rule = None;
+
+    if matches!(args.command, Some(Command::Daemon)) {
+        run_daemon(&args, config, &mut state, &env)?;
+    } else if config.once {
+        update_schedule(&args, &env, &mut config, &mut rule)?;
         try_run_cycle(&config, &mut state, &env)?;
     } else {
         loop {
-            match try_run_cycle(&config, &mut state, &env)? {
-                CycleOutcome::Success => {
-                    // Force glibc allocator to return free memory back to the kernel
-                    #[cfg(target_env = "gnu")]
-                    unsafe {
-                        malloc_trim(0);
-                    }
-                    sleep(Duration::from_secs(config.interval));
+            refresh_schedule(&args, &env, &mut config, &mut rule);
+            run_until_applied(&config, &mut state, &env)?;
+            sleep(config.time_to_next_switch());
+        }
+    }
+
+    Ok(())
+}
+
+/// Repeats [`try_run_cycle`] until a set of wallpapers is applied, returning that set.
+///
+/// # Errors
+///
+/// Returns a [`WallSwitchResult`] if a cycle fails with a non-recoverable error.
+fn run_until_applied(
+    config: &Config,
+    state: &mut State,
+    env: &Environment,
+) -> WallSwitchResult<Vec<FileInfo>> {
+    loop {
+        if let CycleOutcome::Success(images) = try_run_cycle(config, state, env)? {
+            release_memory();
+            return Ok(images);
+        }
+    }
+}
+
+/// Reloads the configuration when another schedule rule (or none) becomes active.
+///
+/// Returns true if the active rule changed since the last call.
+///
+/// # Errors
+///
+/// Returns a [`WallSwitchResult`] if the reloaded configuration is invalid.
+fn update_schedule(
+    args: &Arguments,
+    env: &Environment,
+    config: &mut Config,
+    active: &mut Option<usize>,
+) -> WallSwitchResult<bool> {
+    if config.schedule.is_empty() && active.is_none() {
+        return Ok(false);
+    }
+
+    let rule = config.active_rule(LocalTime::now());
+    if rule == *active {
+        return Ok(false);
+    }
+
+    *config = Config::reload(args, env, config.profile.as_deref(), rule)?;
+    *active = rule;
+
+    if config.verbose {
+        match rule.and_then(|index| config.schedule.get(index)) {
+            Some(rule) => println!("Schedule rule active: {rule}\n"),
+            None => println!("No schedule rule active: using the base settings\n"),
+        }
+    }
+
+    Ok(true)
+}
+
+/// Same as [`update_schedule`] for long-running loops: a configuration file that
+/// fails to reload is reported and the current settings stay in effect.
+fn refresh_schedule(
+    args: &Arguments,
+    env: &Environment,
+    config: &mut Config,
+    active: &mut Option<usize>,
+) -> bool {
+    update_schedule(args, env, config, active).unwrap_or_else(|err| {
+        eprintln!("Schedule reload failed, keeping the current settings: {err}");
+        false
+    })
+}
+
+/// Forces the glibc allocator to return free memory back to the kernel.
+fn release_memory() {
+    #[cfg(target_env = "gnu")]
+    unsafe {
+        malloc_trim(0);
+    }
+}
+
+/// Runtime state of the daemon loop.
+struct Daemon {
+    /// When true, the automatic timer is stopped.
+    paused: bool,
+    /// Instant of the last applied wallpaper set (`None` before the first one).
+    last_switch: Option<Instant>,
+    /// How many cycles back the displayed set is (`0` is the newest record).
+    offset: usize,
+    /// Index of the active schedule rule, if any.
+    rule: Option<usize>,
+}
+
+impl Daemon {
+    /// Returns true if the automatic timer has expired.
+    fn is_due(&self, config: &Config) -> bool {
+        !self.paused && self.time_left(config).is_zero()
+    }
+
+    /// Time until the next automatic switch (or schedule change).
+    fn time_left(&self, config: &Config) -> Duration {
+        let interval = Duration::from_secs(config.interval);
+        self.last_switch.map_or(Duration::ZERO, |last| {
+            interval
+                .saturating_sub(last.elapsed())
+                .min(config.time_to_next_switch())
+        })
+    }
+
+    /// Marks a set as displayed `offset` cycles back and restarts the timer.
+    fn displayed(&mut self, offset: usize) {
+        self.offset = offset;
+        self.last_switch = Some(Instant::now());
+    }
+
+    /// Builds the multi-line reply of the `status` command.
+    fn status(&self, config: &Config, state: &State) -> String {
+        let mut lines = vec![
+            if self.paused { "paused" } else { "running" }.to_string(),
+            format!("interval: {} seconds", config.interval),
+            format!("profile: {}", config.profile.as_deref().unwrap_or("none")),
+        ];
+
+        if let Some(rule) = self.rule.and_then(|index| config.schedule.get(index)) {
+            lines.push(format!("schedule: {rule}"));
+        }
+
+        if !self.paused {
+            let left = self.time_left(config).as_secs();
+            lines.push(format!("next switch in: {left} seconds"));
+        }
+
+        if let Some(current) = state.cycle(self.offset) {
+            lines.push(format!(
+                "wallpapers ({} cycle(s) back, applied {}):",
+                self.offset,
+                current.date_time()
+            ));
+            lines.extend(
+                current
+                    .images()
+                    .iter()
+                    .map(|f| format!("  {}", f.path.display())),
+            );
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Runs the wallpaper loop while serving commands from the control socket.
+///
+/// Instead of sleeping for `config.interval`, the loop waits on the socket until
+/// either a command arrives or the timer expires.
+///
+/// # Errors
+///
+/// Returns a [`WallSwitchResult`] if the socket cannot be bound or an automatic
+/// cycle fails with a non-recoverable error.
+fn run_daemon(
+    args: &Arguments,
+    mut config: Config,
+    state: &mut State,
+    env: &Environment,
+) -> WallSwitchResult<()> {
+    let server = ControlServer::bind(env)?;
+    println!("Listening for commands on {:?}\n", server.path());
+
+    let mut daemon = Daemon {
+        paused: false,
+        last_switch: None,
+        offset: 0,
+        rule: None,
+    };
+
+    loop {
+        let rescheduled = refresh_schedule(args, env, &mut config, &mut daemon.rule);
+
+        if daemon.is_due(&config) || (rescheduled && !daemon.paused) {
+            run_until_applied(&config, state, env)?;
+            daemon.displayed(0);
+        }
+
+        let timeout = (!daemon.paused).then(|| daemon.time_left(&config));
+
+        if let Some(request) = server.wait(timeout)? {
+            if config.verbose {
+                println!("Control command received: {}", request.command);
+            }
+            handle_control_request(request, &mut daemon, args, &mut config, state, env);
+        }
+    }
+}
+
+/// Executes a single control command and replies to the client.
+///
+/// Failures are reported to the client instead of stopping the daemon.
+fn handle_control_request(
+    request: ControlRequest,
+    daemon: &mut Daemon,
+    args: &Arguments,
+    config: &mut Config,
+    state: &mut State,
+    env: &Environment,
+) {
+    match request.command {
+        ControlCommand::Next => match run_until_applied(config, state, env) {
+            Ok(images) => {
+                daemon.displayed(0);
+                request.ok(format!("applied {} image(s)", images.len()));
+            }
+            Err(err) => request.error(err),
+        },
+        ControlCommand::Previous { steps } => {
+            let offset = daemon.offset + steps;
+            match restore_previous(offset, config, state, env) {
+                Ok(record) => {
+                    let msg = format!("restored set from {}", record.date_time());
+                    daemon.displayed(offset);
+                    request.ok(msg);
+                }
+                Err(err) => request.error(err),
+            }
+        }
+        ControlCommand::Pause => {
+            daemon.paused = true;
+            request.ok("paused");
+        }
+        ControlCommand::Resume => {
+            daemon.paused = false;
+            request.ok("resumed");
+        }
+        ControlCommand::Status => request.ok(daemon.status(config, state)),
+        ControlCommand::ReloadConfig => {
+            match Config::reload(args, env, config.profile.as_deref(), daemon.rule) {
+                Ok(new_config) => {
+                    *config = new_config;
+                    request.ok("configuration reloaded");
                 }
-                CycleOutcome::Retry => {
-                    continue;
+                Err(err) => request.error(err),
+            }
+        }
+        ControlCommand::SetInterval { seconds } => match config.set_interval(seconds) {
+            Ok(()) => request.ok(format!("interval set to {seconds} seconds")),
+            Err(err) => request.error(err),
+        },
+        ControlCommand::Profile { ref name } => {
+            match Config::reload(args, env, name.as_deref(), daemon.rule) {
+                Ok(new_config) => {
+                    let message = format!("profile: {}", name.as_deref().unwrap_or("none"));
+                    *config = new_config;
+                    request.ok(message);
                 }
+                Err(err) => request.error(err),
             }
         }
     }
+}
+
+/// Runs the `render` subcommand: composes the inputs into a single canvas and saves it.
+///
+/// Effect settings and presets come from the configuration file (if any), which is
+/// neither validated nor rewritten, so no desktop tooling is required.
+///
+/// # Errors
+///
+/// Returns a [`WallSwitchResult`] if there are more than 255 inputs, or if rendering fails.
+fn render_to_file(render: &RenderArgs, verbose: bool, env: &Environment) -> WallSwitchResult<()> {
+    let config = Config {
+        verbose,
+        ..Config::load_or_default(env)
+    };
+
+    let pictures_per_monitor =
+        u8::try_from(render.inputs.len()).map_err(|_| WallSwitchError::AtMostValue {
+            arg: "--input".to_string(),
+            value: render.inputs.len().to_string(),
+            num: u8::MAX.into(),
+        })?;
+
+    let monitor = Monitor {
+        picture_orientation: render.orientation.clone(),
+        pictures_per_monitor: pictures_per_monitor.max(1),
+        resolution: render.size.clone(),
+        base: render.base.unwrap_or_default(),
+        crop: render.crop,
+        fit: render.fit,
+        layout: render.layout,
+        columns: render.columns,
+        gutter: render.gutter,
+        margin: render.margin,
+        corner_radius: render.corner_radius,
+        shadow: render.shadow,
+        ..Monitor::default()
+    };
+
+    let images: Vec<FileInfo> = render
+        .inputs
+        .iter()
+        .map(|path| FileInfo {
+            path: path.clone(),
+            ..FileInfo::default()
+        })
+        .collect();
+
+    let effect = render.effect.unwrap_or(config.effect);
+    let seed = render.seed.or(config.seed).unwrap_or_else(rand);
+
+    init_rayon_thread_pool(config.max_threads_percent, config.verbose);
+
+    let record = render_wallpaper(&images, &monitor, effect, seed, &config, &render.output)?;
+
+    let preset = record
+        .preset
+        .map(|name| format!(" ({name})"))
+        .unwrap_or_default();
+
+    println!(
+        "Rendered {} ({}x{}), effect: {}{preset}, seed {seed}",
+        render.output.display(),
+        monitor.resolution.width,
+        monitor.resolution.height,
+        record.effect.get_name()
+    );
 
     Ok(())
 }
 
+/// Re-applies the wallpaper set recorded `back` cycles ago and returns its record.
+///
+/// # Errors
+///
+/// Returns [`WallSwitchError::HistoryUnavailable`] if the history is shorter than `back`,
+/// or any error raised while re-applying the set.
+fn restore_previous(
+    back: usize,
+    config: &Config,
+    state: &State,
+    env: &Environment,
+) -> WallSwitchResult<CycleRecord> {
+    let record = state
+        .cycle(back)
+        .cloned()
+        .ok_or(WallSwitchError::HistoryUnavailable {
+            back,
+            available: state.history.len(),
+        })?;
+
+    let mut images = record.images();
+    if !images.is_empty() {
+        images.update_number();
+        print!("{}", SliceDisplay(&images));
+        println!();
+    }
+
+    restore_wallpaper(&record, config, env)?;
+
+    Ok(record)
+}
+
 /// Configures the global Rayon thread pool size based on the configured CPU percentage.
 ///
 /// This uses a static [`Once`] lock to guarantee that `build_global`
@@ -218,6 +603,7 @@ pub fn gather_files(config: &Config, state: &mut State) -> WallSwitchResult<Vec<
         {
             file.hash = cache.hash.clone();
             file.dimension = cache.dimension.clone();
+            file.focal_point = cache.focal_point;
             cached_files.push(file);
             continue;
         }
@@ -242,11 +628,16 @@ pub fn gather_files(config: &Config, state: &mut State) -> WallSwitchResult<Vec<
                 mtime: file.mtime,
                 hash: file.hash.clone(),
                 dimension: file.dimension.clone(),
+                focal_point: None,
             },
         );
     }
 
-    let all_files = cached_files.into_iter().chain(needs_hash);
+    // Sort by path so that the seeded shuffle and the kept duplicate do not depend on
+    // which files happened to be cached
+    let mut all_files: Vec<FileInfo> = cached_files.into_iter().chain(needs_hash).collect();
+    all_files.sort_by(|a, b| a.path.cmp(&b.path));
+
     let mut files = Vec::new();
     let mut seen_hashes = std::collections::HashSet::new();
 
@@ -307,20 +698,66 @@ fn try_run_cycle(
     state: &mut State,
     env: &Environment,
 ) -> WallSwitchResult<CycleOutcome> {
+    // A configured seed makes the cycles reproducible: each cycle derives its own seed
+    // from it and the cycle counter, so a long-running loop does not repeat itself.
+    // Without one, draw a fresh seed to record.
+    let cycle = state.next_cycle();
+    let seed = config
+        .seed
+        .map(|seed| derive_seed(seed, cycle))
+        .unwrap_or_else(rand);
+
+    if config.verbose {
+        println!("Random seed: {seed}");
+    }
+
+    with_seed(seed, || run_seeded_cycle(seed, config, state, env))
+}
+
+/// Selects, validates and applies one wallpaper set with every random draw driven by `seed`.
+fn run_seeded_cycle(
+    seed: u64,
+    config: &Config,
+    state: &mut State,
+    env: &Environment,
+) -> WallSwitchResult<CycleOutcome> {
+    // Detect the screens once: images are matched, compiled and applied against them
+    let screens = Screens::detect(config);
+
+    // Phase 0: Monitors with generated bases only need no images at all
+    if config.get_number_of_images() == 0 {
+        return apply_cycle_images(Vec::new(), &screens, seed, config, state, env);
+    }
+
     // Phase 1: Retrieve candidate files and determine optimal core counts
-    let candidates = get_images(config, state)?;
+    let candidates = match get_images(config, state) {
+        Err(WallSwitchError::NoImages { paths }) if config.procedural_fallback => {
+            println!("No images found in {paths:?}: drawing procedural wallpapers instead.\n");
+            let procedural = config.procedural();
+            let screens = Screens::resolve(&procedural, screens.outputs);
+            return apply_cycle_images(Vec::new(), &screens, seed, &procedural, state, env);
+        }
+        result => result?,
+    };
     let needed = config.get_number_of_images();
 
     if config.verbose {
         display_files(&candidates, config);
     }
 
+    // Aspect matching probes a wider pool so that each slot has a choice
+    let wanted = if config.matches_aspect() {
+        needed * MATCH_POOL_FACTOR
+    } else {
+        needed
+    };
+
     let batch_size = candidates.get_optimal_cores();
-    let mut valid_pool = accumulate_valid_pool(config, state, env, candidates, needed, batch_size);
+    let valid_pool = accumulate_valid_pool(config, state, env, candidates, wanted, batch_size);
 
     // Phase 2: Quorum Validation and Application
     if valid_pool.len() >= needed {
-        let cycle_images: Vec<FileInfo> = valid_pool.drain(0..needed).collect();
+        let cycle_images = select_cycle_images(valid_pool, needed, &screens.monitors, config);
 
         if config.verbose {
             println!(
@@ -330,49 +767,64 @@ fn try_run_cycle(
             );
         }
 
-        // Output current processing queue details on terminal
+        return apply_cycle_images(cycle_images, &screens, seed, config, state, env);
+    }
+
+    // Phase 3: Self-Healing Trigger
+    handle_history_reset_and_retry(config, state, env, needed, valid_pool.len())
+}
+
+/// Displays the selected images, applies them and records the cycle in the history.
+///
+/// `cycle_images` is empty when every monitor draws a generated base.
+/// A corrupt image is skipped for the rest of the rotation and yields [`CycleOutcome::Retry`].
+fn apply_cycle_images(
+    cycle_images: Vec<FileInfo>,
+    screens: &Screens,
+    seed: u64,
+    config: &Config,
+    state: &mut State,
+    env: &Environment,
+) -> WallSwitchResult<CycleOutcome> {
+    // Output current processing queue details on terminal
+    if !cycle_images.is_empty() {
         print!("{}", SliceDisplay(&cycle_images));
         println!();
+    }
+
+    // Apply wallpapers using the selected OS-level backend (with unified on-the-fly monitor rendering)
+    match set_wallpaper(&cycle_images, screens, seed, config, env) {
+        Ok(record) => {
+            // Record successful images in history and save state to disk (only if not simulating)
+            if !config.dry_run {
+                state.history.push(record);
+                state.save(env)?;
+            }
+
+            Ok(CycleOutcome::Success(cycle_images))
+        }
+        Err(err) => {
+            if let Some(corrupt_path) = err.get_corrupt_path() {
+                // Log using the same layout as invalid dimension warnings
+                log_invalid_image_error(&corrupt_path, &err);
+
+                // Remove from active state hash cache so we don't try probing it again
+                state.hashes.remove(&corrupt_path);
 
-        // Apply wallpapers using the selected OS-level backend (with unified on-the-fly monitor rendering)
-        match set_wallpaper(&cycle_images, config, env) {
-            Ok(_) => {
-                // Record successful images in history and save state to disk (only if not simulating)
+                // Push to history to avoid selecting it in next sweeps
+                state.skipped.push(corrupt_path);
                 if !config.dry_run {
-                    for fig in &cycle_images {
-                        state.history.push(fig.path.clone());
-                    }
-                    state.save(env)?;
+                    let _ = state.save(env);
                 }
 
-                return Ok(CycleOutcome::Success);
-            }
-            Err(err) => {
-                if let Some(corrupt_path) = err.get_corrupt_path() {
-                    // Log using the same layout as invalid dimension warnings
-                    log_invalid_image_error(&corrupt_path, &err);
-
-                    // Remove from active state hash cache so we don't try probing it again
-                    state.hashes.remove(&corrupt_path);
-
-                    // Push to history to avoid selecting it in next sweeps
-                    state.history.push(corrupt_path);
-                    if !config.dry_run {
-                        let _ = state.save(env);
-                    }
-
-                    // Direct the iterative runner to skip sleep and self-heal instantly
-                    return Ok(CycleOutcome::Retry);
-                } else {
-                    // Return system errors immediately
-                    return Err(err);
-                }
+                // Direct the iterative runner to skip sleep and self-heal instantly
+                Ok(CycleOutcome::Retry)
+            } else {
+                // Return system errors immediately
+                Err(err)
             }
         }
     }
-
-    // Phase 3: Self-Healing Trigger
-    handle_history_reset_and_retry(config, state, env, needed, valid_pool.len())
 }
 
 /// Iteratively accumulates candidate files into batches, validating them concurrently.
@@ -424,14 +876,14 @@ fn handle_history_reset_and_retry(
     needed: usize,
     found: usize,
 ) -> WallSwitchResult<CycleOutcome> {
-    if !state.history.is_empty() {
+    if state.has_seen() {
         if config.verbose {
             println!(
                 "\nQuorum failed: Needed {}, but found only {}. Resetting history for a full disk search...",
                 needed, found
             );
         }
-        state.history.clear();
+        state.reset_rotation();
         if !config.dry_run {
             state.save(env)?;
         }
@@ -453,16 +905,18 @@ pub fn get_images(config: &Config, state: &mut State) -> WallSwitchResult<Vec<Fi
     let images: Vec<FileInfo> = gather_files(config, state)?;
 
     if images.is_empty() {
-        let directories = config.directories.clone();
+        let directories = config.directories.iter().map(|d| d.path.clone()).collect();
         return Err(WallSwitchError::NoImages { paths: directories });
     }
 
-    // Filter out images that are already in the recent history
-    let mut pool: Vec<FileInfo> = images
-        .iter()
-        .filter(|img| !state.history.contains(&img.path))
-        .cloned()
-        .collect();
+    // Filter out images that are already in the current rotation. Weighted collections
+    // rotate on their own, so that a small favourite collection keeps coming back.
+    let weighted = config.directories.iter().any(Directory::is_weighted);
+    let mut pool: Vec<FileInfo> = state.unseen(images.clone(), |path| {
+        weighted
+            .then(|| collection_of(path, &config.directories))
+            .flatten()
+    });
 
     // The required number of images for ONE complete cycle
     let needed_images = config.get_number_of_images();
@@ -474,14 +928,14 @@ pub fn get_images(config: &Config, state: &mut State) -> WallSwitchResult<Vec<Fi
                 "Image pool exhausted (less than {needed_images} unseen images). Resetting history cycle."
             );
         }
-        state.history.clear();
+        state.reset_rotation();
         pool = images.clone();
     }
 
     pool.update_number();
 
     if !config.sort {
-        pool.shuffle();
+        pool = weighted_shuffle(pool, &config.directories);
     }
 
     Ok(pool)
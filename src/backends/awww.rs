@@ -1,6 +1,6 @@
 use crate::{
-    CommandExt, Config, DaemonConfig, DaemonManager, FileInfo, WallSwitchResult, WallpaperBackend,
-    detect_monitors, get_random_integer,
+    CommandExt, Config, DaemonConfig, DaemonManager, FileInfo, Output, WallSwitchResult,
+    WallpaperBackend, assign_canvases, get_random_integer,
 };
 use std::process::Command;
 
@@ -8,11 +8,9 @@ use std::process::Command;
 pub struct AwwwBackend;
 
 impl WallpaperBackend for AwwwBackend {
-    fn apply(images: &[FileInfo], config: &Config) -> WallSwitchResult<()> {
-        let monitors = detect_monitors(config)?;
-
+    fn apply(images: &[FileInfo], outputs: &[Output], config: &Config) -> WallSwitchResult<()> {
         if config.verbose {
-            println!("monitors:\n{monitors:#?}\n");
+            println!("monitors:\n{outputs:#?}\n");
         }
 
         // Declarative configuration: The backend only knows WHAT to run,
@@ -25,12 +23,17 @@ impl WallpaperBackend for AwwwBackend {
         // Logic is DRY: DaemonManager handles kill, hook, spawn, and polling.
         DaemonManager::ensure_running(config, &daemon_cfg)?;
 
-        for (image, monitor) in images.iter().cycle().zip(monitors.iter()) {
+        // Bind each compiled canvas to its output by name (index for unnamed monitors).
+        for (canvas, monitor) in assign_canvases(&config.monitors, outputs) {
+            let Some(image) = images.get(canvas) else {
+                continue;
+            };
+
             let effect = get_transition_effect(config);
 
             let mut wall_cmd = Command::new("awww");
             wall_cmd
-                .args(["img", "-o", monitor])
+                .args(["img", "-o", &monitor.name])
                 .arg(&image.path)
                 .args(["--transition-type", &effect])
                 .args([
@@ -44,7 +47,7 @@ impl WallpaperBackend for AwwwBackend {
             if config.dry_run {
                 println!("[DRY-RUN] Would execute: {:?}", wall_cmd);
             } else {
-                wall_cmd.run_with_config(config, &format!("Apply awww on {monitor}"))?;
+                wall_cmd.run_with_config(config, &format!("Apply awww on {}", monitor.name))?;
             }
         }
 
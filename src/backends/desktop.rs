@@ -114,6 +114,14 @@ impl Desktop {
 
         Desktop::Openbox
     }
+
+    /// Returns true if the wallpaper tools of the desktop address outputs by name.
+    pub fn is_wayland(self) -> bool {
+        matches!(
+            self,
+            Desktop::Hyprland | Desktop::Niri | Desktop::Labwc | Desktop::Mango | Desktop::Wayland
+        )
+    }
 }
 
 //----------------------------------------------------------------------------//
@@ -1,4 +1,7 @@
-use crate::{CommandExt, Config, Desktop, WallSwitchError, WallSwitchResult, is_installed};
+use crate::{
+    CommandExt, Config, Desktop, Dimension, Monitor, Output, WallSwitchError, WallSwitchResult,
+    apply_detected_geometry, apply_scale, is_installed,
+};
 use std::{
     fs,
     io::{Write, stdout},
@@ -11,60 +14,13 @@ use std::{
 ///
 /// Under startup environments, the window manager IPC socket may not be immediately
 /// ready. This function polls the detection mechanism for up to 3 seconds before failing.
-pub fn detect_monitors(config: &Config) -> WallSwitchResult<Vec<String>> {
+pub fn detect_monitors(config: &Config) -> WallSwitchResult<Vec<Output>> {
     let max_wait = std::time::Duration::from_secs_f32(3.0);
     let step = std::time::Duration::from_secs_f32(0.05); // 50ms is a safe and CPU-friendly polling interval
     let start_time = std::time::Instant::now();
 
     while start_time.elapsed() < max_wait {
-        let mut monitors = Vec::new();
-
-        // 1. Try Desktop-specific tools first
-        match config.desktop {
-            Desktop::Niri if is_installed("niri") => {
-                if let Ok(out) = Command::new("niri").args(["msg", "outputs"]).output() {
-                    monitors = parse_niri(&String::from_utf8_lossy(&out.stdout));
-                }
-            }
-            Desktop::Hyprland if is_installed("hyprctl") => {
-                if let Ok(out) = Command::new("hyprctl").arg("monitors").output() {
-                    monitors = parse_hyprland(&String::from_utf8_lossy(&out.stdout));
-                }
-            }
-            Desktop::Xfce if is_installed("xfconf-query") => {
-                let active_xrandr_monitors = get_active_xrandr_monitors(config);
-                let _ = prune_stale_xfce_configs(config, &active_xrandr_monitors);
-
-                let mut cmd = Command::new("xfconf-query");
-                cmd.args([
-                    "--channel",
-                    "xfce4-desktop",
-                    "--property",
-                    "/backdrop",
-                    "--list",
-                ]);
-                if let Ok(out) = cmd.run_with_config(config, "xfconf-query") {
-                    monitors = parse_xfce(
-                        &String::from_utf8_lossy(&out.stdout),
-                        &active_xrandr_monitors,
-                    );
-                }
-            }
-            _ => {}
-        }
-
-        // 2. Generic Wayland fallback (wlr-randr)
-        if monitors.is_empty()
-            && is_installed("wlr-randr")
-            && let Ok(out) = Command::new("wlr-randr").output()
-        {
-            monitors = parse_wlr_randr(&String::from_utf8_lossy(&out.stdout));
-        }
-
-        // 3. Hardware fallback (DRM Sysfs - Linux Kernel)
-        if monitors.is_empty() {
-            monitors = detect_drm_monitors();
-        }
+        let monitors = detect_outputs(config);
 
         // If at least one active monitor was successfully detected, proceed immediately
         if !monitors.is_empty() {
@@ -90,16 +46,175 @@ pub fn detect_monitors(config: &Config) -> WallSwitchResult<Vec<String>> {
         sleep(step);
     }
 
-    // 4. Fatal Error: Timeout reached and no monitors could be found
+    // Fatal Error: Timeout reached and no monitors could be found
     Err(WallSwitchError::NoMonitors(
         "any system tool (X11/Wayland/DRM) within the initialization timeout".to_string(),
     ))
 }
 
+/// Monitors and outputs of a single detection pass, shared by every step of a cycle.
+///
+/// Canvases are compiled against `monitors` and bound to `outputs` by the backend,
+/// so both must come from the same pass even if a screen is hotplugged meanwhile.
+#[derive(Debug, Clone, Default)]
+pub struct Screens {
+    /// Effective monitor list, in physical pixels (see [`resolve_monitors`]).
+    pub monitors: Vec<Monitor>,
+    /// Active outputs (empty if detection was not needed or found nothing).
+    pub outputs: Vec<Output>,
+}
+
+impl Screens {
+    /// Detects the outputs once and resolves the configured monitors against them.
+    ///
+    /// Detection runs a single pass (the startup retry loop is [`detect_monitors`]),
+    /// only when `detect_resolution` is enabled or the desktop addresses outputs by name.
+    pub fn detect(config: &Config) -> Self {
+        let outputs = if config.detect_resolution || config.desktop.is_wayland() {
+            detect_outputs(config)
+        } else {
+            Vec::new()
+        };
+
+        Self::resolve(config, outputs)
+    }
+
+    /// Resolves the configured monitors against already detected `outputs`.
+    pub fn resolve(config: &Config, outputs: Vec<Output>) -> Self {
+        Screens {
+            monitors: resolve_monitors(config, &outputs),
+            outputs,
+        }
+    }
+}
+
+/// Resolves the effective monitor list used to render the current cycle.
+///
+/// When `detect_resolution` is enabled, each configured resolution is replaced by the
+/// native mode size of the detected output, rotated by its transform, and monitors
+/// without a `position` take the position of their output. Without any detected
+/// `outputs`, the configured geometry is used instead.
+///
+/// Resolutions of the returned monitors are in physical pixels (see [`apply_scale`]).
+pub fn resolve_monitors(config: &Config, outputs: &[Output]) -> Vec<Monitor> {
+    if !config.detect_resolution {
+        return apply_scale(&config.monitors);
+    }
+
+    if outputs.is_empty() {
+        if config.verbose {
+            eprintln!("No output detected. Using the configured monitor resolutions.");
+        }
+        return apply_scale(&config.monitors);
+    }
+
+    let monitors = apply_detected_geometry(&config.monitors, outputs);
+    if config.verbose {
+        for (index, monitor) in monitors.iter().enumerate() {
+            print!(
+                "Monitor {index} native resolution: {}x{}",
+                monitor.resolution.width, monitor.resolution.height
+            );
+            match monitor.position {
+                Some(position) => println!(" at {},{}", position.x, position.y),
+                None => println!(),
+            }
+        }
+    }
+    monitors
+}
+
+/// Runs a single pass of the detection fallback chain, without waiting.
+///
+/// Returns an empty vector if no tool reported an active output.
+pub fn detect_outputs(config: &Config) -> Vec<Output> {
+    let mut monitors = Vec::new();
+
+    // 1. Try Desktop-specific tools first
+    match config.desktop {
+        Desktop::Niri if is_installed("niri") => {
+            if let Ok(out) = Command::new("niri").args(["msg", "outputs"]).output() {
+                monitors = parse_niri(&String::from_utf8_lossy(&out.stdout));
+            }
+        }
+        Desktop::Hyprland if is_installed("hyprctl") => {
+            if let Ok(out) = Command::new("hyprctl").arg("monitors").output() {
+                monitors = parse_hyprland(&String::from_utf8_lossy(&out.stdout));
+            }
+        }
+        _ => {}
+    }
+
+    // 2. Generic Wayland fallback (wlr-randr)
+    if monitors.is_empty()
+        && is_installed("wlr-randr")
+        && let Ok(out) = Command::new("wlr-randr").output()
+    {
+        monitors = parse_wlr_randr(&String::from_utf8_lossy(&out.stdout));
+    }
+
+    // 3. Generic X11 fallback (xrandr)
+    if monitors.is_empty()
+        && is_installed("xrandr")
+        && let Ok(out) = Command::new("xrandr").arg("--listactivemonitors").output()
+    {
+        monitors = parse_xrandr_outputs(&String::from_utf8_lossy(&out.stdout));
+    }
+
+    // 4. Hardware fallback (DRM Sysfs - Linux Kernel)
+    if monitors.is_empty() {
+        monitors = detect_drm_monitors();
+    }
+
+    monitors
+}
+
+/// Detects the XFCE `last-image` property paths that should receive a wallpaper.
+///
+/// Active hardware reported by xrandr is matched against the existing `xfconf`
+/// properties; stale entries are pruned and missing ones are synthesized.
+pub fn detect_xfce_properties(config: &Config) -> WallSwitchResult<Vec<String>> {
+    if !is_installed("xfconf-query") {
+        return Err(WallSwitchError::UnableToFind("xfconf-query".to_string()));
+    }
+
+    let active_xrandr_monitors = get_active_xrandr_monitors(config);
+    let _ = prune_stale_xfce_configs(config, &active_xrandr_monitors);
+
+    let mut cmd = Command::new("xfconf-query");
+    cmd.args([
+        "--channel",
+        "xfce4-desktop",
+        "--property",
+        "/backdrop",
+        "--list",
+    ]);
+    let out = cmd.run_with_config(config, "xfconf-query")?;
+
+    let properties = parse_xfce(
+        &String::from_utf8_lossy(&out.stdout),
+        &active_xrandr_monitors,
+    );
+
+    if properties.is_empty() {
+        return Err(WallSwitchError::NoMonitors("xfconf-query".to_string()));
+    }
+
+    Ok(properties)
+}
+
 /// Get active X11 monitors via xrandr to filter out stale configurations.
 ///
 /// Runs `xrandr --listactivemonitors` and safely parses the output.
 pub fn get_active_xrandr_monitors(config: &Config) -> Vec<String> {
+    get_active_xrandr_outputs(config)
+        .into_iter()
+        .map(|output| output.name)
+        .collect()
+}
+
+/// Get active X11 outputs (with geometry) via `xrandr --listactivemonitors`.
+pub fn get_active_xrandr_outputs(config: &Config) -> Vec<Output> {
     let mut monitors = Vec::new();
 
     if is_installed("xrandr") {
@@ -107,7 +222,7 @@ pub fn get_active_xrandr_monitors(config: &Config) -> Vec<String> {
         cmd.args(["--listactivemonitors"]);
         if let Ok(out) = cmd.run_with_config(config, "xrandr") {
             let stdout = String::from_utf8_lossy(&out.stdout);
-            monitors = parse_xrandr(&stdout);
+            monitors = parse_xrandr_outputs(&stdout);
         }
     }
 
@@ -115,7 +230,17 @@ pub fn get_active_xrandr_monitors(config: &Config) -> Vec<String> {
 }
 
 /// Pure parser for xrandr output.
+///
+/// Line format: ` 0: +*DP-1 3840/621x2160/341+0+0  DP-1`
 pub fn parse_xrandr(stdout: &str) -> Vec<String> {
+    parse_xrandr_outputs(stdout)
+        .into_iter()
+        .map(|output| output.name)
+        .collect()
+}
+
+/// Pure parser for xrandr output, including geometry.
+pub fn parse_xrandr_outputs(stdout: &str) -> Vec<Output> {
     stdout
         .lines()
         .filter_map(|line| {
@@ -125,7 +250,14 @@ pub fn parse_xrandr(stdout: &str) -> Vec<String> {
                 if let Some(prefix) = first_token.strip_suffix(':')
                     && prefix.parse::<usize>().is_ok()
                 {
-                    return tokens.last().map(|&s| s.to_string());
+                    let mut output = Output::named(*tokens.last()?);
+                    if let Some((width, height, x, y)) = parse_xrandr_geometry(tokens[2]) {
+                        output.width = width;
+                        output.height = height;
+                        output.x = x;
+                        output.y = y;
+                    }
+                    return Some(output);
                 }
             }
             None
@@ -133,6 +265,42 @@ pub fn parse_xrandr(stdout: &str) -> Vec<String> {
         .collect()
 }
 
+/// Parses an xrandr geometry token: "3840/621x2160/341+3840+0".
+fn parse_xrandr_geometry(token: &str) -> Option<(u64, u64, i64, i64)> {
+    let (width_part, rest) = token.split_once('x')?;
+    let width = width_part.split('/').next()?.parse().ok()?;
+
+    // The position starts at the first sign character after the height.
+    let pos_idx = rest.find(['+', '-'])?;
+    let (height_part, position) = rest.split_at(pos_idx);
+    let height = height_part.split('/').next()?.parse().ok()?;
+
+    let (x, y) = parse_signed_pair(position)?;
+    Some((width, height, x, y))
+}
+
+/// Parses an X11-style signed offset pair ("+3840+0", "-1440+0").
+fn parse_signed_pair(position: &str) -> Option<(i64, i64)> {
+    let split_idx = position[1..].find(['+', '-'])? + 1;
+    let (x, y) = position.split_at(split_idx);
+    Some((x.parse().ok()?, y.parse().ok()?))
+}
+
+/// Parses a "x,y", "x, y" or "xxy" position pair.
+fn parse_position(text: &str) -> Option<(i64, i64)> {
+    let text = text.trim();
+    let (x, y) = text.split_once(',').or_else(|| text.rsplit_once('x'))?;
+    Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+}
+
+/// Copies a parsed mode size into the output geometry.
+fn set_mode(output: &mut Output, mode: &str) {
+    if let Ok(dimension) = mode.parse::<Dimension>() {
+        output.width = dimension.width;
+        output.height = dimension.height;
+    }
+}
+
 /// XFCE Logic: Matches active hardware (xrandr) with XFCE properties.
 /// If a monitor is active but has no XFCE property yet, we synthesize one.
 pub fn parse_xfce(stdout: &str, active_monitors: &[String]) -> Vec<String> {
@@ -174,6 +342,19 @@ pub fn parse_xfce(stdout: &str, active_monitors: &[String]) -> Vec<String> {
     final_properties
 }
 
+/// Extracts the output name from an XFCE backdrop property path.
+///
+/// "/backdrop/screen0/monitorDP-1/workspace0/last-image" -> "DP-1"
+pub fn xfce_output_name(property: &str) -> &str {
+    let segment = property
+        .split('/')
+        .find(|segment| segment.starts_with("monitor"))
+        .or_else(|| property.split('/').nth(3))
+        .unwrap_or(property);
+
+    segment.strip_prefix("monitor").unwrap_or(segment)
+}
+
 /// Prunes stale XFCE configurations that no longer correspond to active hardware.
 pub fn prune_stale_xfce_configs(
     config: &Config,
@@ -233,43 +414,154 @@ pub fn prune_stale_xfce_configs(
     Ok(())
 }
 
-/// Pure parser for Niri output.
-pub fn parse_niri(stdout: &str) -> Vec<String> {
-    stdout
-        .lines()
-        .filter(|line| line.starts_with("Output"))
-        .filter_map(|line| {
-            let start = line.rfind('(')?;
-            let end = line.rfind(')')?;
-            if start < end {
-                Some(line[start + 1..end].to_string())
-            } else {
-                None
+/// Pure parser for Niri output (`niri msg outputs`).
+///
+/// ```text
+/// Output "Dell Inc. DELL U2720Q" (DP-1)
+///   Current mode: 3840x2160 @ 59.997 Hz (preferred)
+///   Logical position: 0, 0
+///   Scale: 1.5
+///   Transform: normal
+/// ```
+pub fn parse_niri(stdout: &str) -> Vec<Output> {
+    let mut outputs: Vec<Output> = Vec::new();
+
+    for line in stdout.lines() {
+        if line.starts_with("Output") {
+            let start = line.rfind('(');
+            let end = line.rfind(')');
+            if let (Some(start), Some(end)) = (start, end)
+                && start < end
+            {
+                outputs.push(Output::named(&line[start + 1..end]));
             }
-        })
-        .collect()
+            continue;
+        }
+
+        let Some(output) = outputs.last_mut() else {
+            continue;
+        };
+
+        let Some((key, value)) = line.trim().split_once(':') else {
+            continue;
+        };
+
+        match key {
+            "Current mode" | "Mode" => set_mode(output, value),
+            "Logical position" => {
+                if let Some((x, y)) = parse_position(value) {
+                    output.x = x;
+                    output.y = y;
+                }
+            }
+            "Scale" => output.scale = value.trim().parse().unwrap_or(1.0),
+            "Transform" => output.transform = value.parse().unwrap_or_default(),
+            _ => {}
+        }
+    }
+
+    outputs
 }
 
-/// Pure parser for Hyprland output.
-pub fn parse_hyprland(stdout: &str) -> Vec<String> {
-    stdout
-        .lines()
-        .filter(|line| line.starts_with("Monitor"))
-        .filter_map(|line| line.split_whitespace().nth(1).map(String::from))
-        .collect()
+/// Pure parser for Hyprland output (`hyprctl monitors`).
+///
+/// ```text
+/// Monitor DP-1 (ID 0):
+///     2560x1440@143.99600 at 0x0
+///     scale: 1.00
+///     transform: 0
+/// ```
+pub fn parse_hyprland(stdout: &str) -> Vec<Output> {
+    let mut outputs: Vec<Output> = Vec::new();
+
+    for line in stdout.lines() {
+        if line.starts_with("Monitor") {
+            if let Some(name) = line.split_whitespace().nth(1) {
+                outputs.push(Output::named(name));
+            }
+            continue;
+        }
+
+        let Some(output) = outputs.last_mut() else {
+            continue;
+        };
+
+        let line = line.trim();
+        if let Some((mode, position)) = line.split_once(" at ")
+            && mode.contains('@')
+        {
+            set_mode(output, mode);
+            if let Some((x, y)) = parse_position(position) {
+                output.x = x;
+                output.y = y;
+            }
+        } else if let Some(scale) = line.strip_prefix("scale:") {
+            output.scale = scale.trim().parse().unwrap_or(1.0);
+        } else if let Some(transform) = line.strip_prefix("transform:") {
+            output.transform = transform.parse().unwrap_or_default();
+        }
+    }
+
+    outputs
 }
 
 /// Pure parser for wlr-randr output.
-pub fn parse_wlr_randr(stdout: &str) -> Vec<String> {
-    stdout
-        .lines()
-        .filter(|line| !line.starts_with(' ') && !line.is_empty())
-        .filter_map(|line| line.split_whitespace().next().map(String::from))
+///
+/// Disabled outputs (`Enabled: no`) are discarded.
+///
+/// ```text
+/// DP-1 "Dell Inc. DELL U2720Q"
+///   Enabled: yes
+///   Modes:
+///     3840x2160 px, 59.997002 Hz (preferred, current)
+///   Position: 0,0
+///   Transform: normal
+///   Scale: 1.500000
+/// ```
+pub fn parse_wlr_randr(stdout: &str) -> Vec<Output> {
+    let mut outputs: Vec<(Output, bool)> = Vec::new();
+
+    for line in stdout.lines() {
+        if !line.starts_with(' ') && !line.is_empty() {
+            if let Some(name) = line.split_whitespace().next() {
+                outputs.push((Output::named(name), true));
+            }
+            continue;
+        }
+
+        let Some((output, enabled)) = outputs.last_mut() else {
+            continue;
+        };
+
+        let line = line.trim();
+        if line.contains(" px,") && line.contains("current") {
+            set_mode(output, line);
+        } else if let Some((key, value)) = line.split_once(':') {
+            match key {
+                "Enabled" => *enabled = value.trim() != "no",
+                "Position" => {
+                    if let Some((x, y)) = parse_position(value) {
+                        output.x = x;
+                        output.y = y;
+                    }
+                }
+                "Scale" => output.scale = value.trim().parse().unwrap_or(1.0),
+                "Transform" => output.transform = value.parse().unwrap_or_default(),
+                _ => {}
+            }
+        }
+    }
+
+    outputs
+        .into_iter()
+        .filter_map(|(output, enabled)| enabled.then_some(output))
         .collect()
 }
 
 /// Hardware DRM parser for Linux kernel.
-fn detect_drm_monitors() -> Vec<String> {
+///
+/// The first line of `/sys/class/drm/<card>-<connector>/modes` is the preferred mode.
+fn detect_drm_monitors() -> Vec<Output> {
     let mut monitors = Vec::new();
     let drm_path = PathBuf::from("/sys/class/drm");
 
@@ -282,7 +574,13 @@ fn detect_drm_monitors() -> Vec<String> {
                     && status.trim() == "connected"
                     && let Some(idx) = name.find('-')
                 {
-                    monitors.push(name[idx + 1..].to_string());
+                    let mut output = Output::named(&name[idx + 1..]);
+                    if let Ok(modes) = fs::read_to_string(entry.path().join("modes"))
+                        && let Some(preferred) = modes.lines().next()
+                    {
+                        set_mode(&mut output, preferred);
+                    }
+                    monitors.push(output);
                 }
             }
         }
@@ -297,10 +595,14 @@ fn detect_drm_monitors() -> Vec<String> {
 #[cfg(test)]
 mod tests_detector {
     use super::*;
+    use crate::Transform;
 
     #[test]
     fn test_monitor_parsers() {
         let expected = vec!["DP-1", "DP-2"];
+        let names = |outputs: Vec<Output>| -> Vec<String> {
+            outputs.into_iter().map(|output| output.name).collect()
+        };
 
         // Mocked xrandr output with malicious/unexpected edge cases
         let xrandr_mock = "\
@@ -317,7 +619,7 @@ Output eDP-1 (DP-1)
   Mode: 1920x1080
 Output HDMI-A-1 (DP-2)
   Mode: 1920x1080";
-        assert_eq!(parse_niri(niri_mock), expected);
+        assert_eq!(names(parse_niri(niri_mock)), expected);
 
         // Mocked Hyprland output
         let hypr_mock = "\
@@ -325,7 +627,7 @@ Monitor DP-1 (ID 0):
   1920x1080@60.00000
 Monitor DP-2 (ID 1):
   1920x1080@60.00000";
-        assert_eq!(parse_hyprland(hypr_mock), expected);
+        assert_eq!(names(parse_hyprland(hypr_mock)), expected);
 
         // Mocked wlr-randr output
         let wlr_mock = "\
@@ -333,7 +635,7 @@ DP-1 \"Manufacturer X\"
   Position: 0,0
 DP-2 \"Manufacturer Y\"
   Position: 1920,0";
-        assert_eq!(parse_wlr_randr(wlr_mock), expected);
+        assert_eq!(names(parse_wlr_randr(wlr_mock)), expected);
 
         // Mocked XFCE output with stale/duplicate entries
         let xfce_mock = "\
@@ -352,6 +654,12 @@ DP-2 \"Manufacturer Y\"
         ];
         assert_eq!(parse_xfce(xfce_mock, &active), xfce_expected);
 
+        assert_eq!(xfce_output_name(xfce_expected[1]), "DP-2");
+        assert_eq!(
+            xfce_output_name("/backdrop/screen0/HDMI-1/workspace0/last-image"),
+            "HDMI-1"
+        );
+
         // Test fallback if xrandr doesn't match anything
         let empty_active = vec![];
         let xfce_expected_fallback = vec![
@@ -362,4 +670,71 @@ DP-2 \"Manufacturer Y\"
         ];
         assert_eq!(parse_xfce(xfce_mock, &empty_active), xfce_expected_fallback);
     }
+
+    #[test]
+    fn test_monitor_geometry_parsers() {
+        let xrandr_mock = "\
+Monitors: 2
+ 0: +*DP-1 2560/597x1440/336+1440+0  DP-1
+ 1: +HDMI-A-1 1440/597x2560/336+0-560  HDMI-A-1";
+        let outputs = parse_xrandr_outputs(xrandr_mock);
+        assert_eq!((outputs[0].width, outputs[0].height), (2560, 1440));
+        assert_eq!((outputs[0].x, outputs[0].y), (1440, 0));
+        assert_eq!((outputs[1].width, outputs[1].height), (1440, 2560));
+        assert_eq!((outputs[1].x, outputs[1].y), (0, -560));
+
+        let hypr_mock = "\
+Monitor DP-1 (ID 0):
+\t3840x2160@59.99700 at 0x0
+\tdescription: Dell Inc. DELL U2720Q
+\tscale: 1.50
+\ttransform: 0
+Monitor HDMI-A-1 (ID 1):
+\t1920x1080@60.00000 at 2560x0
+\tscale: 1.00
+\ttransform: 1";
+        let outputs = parse_hyprland(hypr_mock);
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[0].resolution().unwrap().width, 3840);
+        assert_eq!(outputs[0].scale, 1.5);
+        assert_eq!((outputs[1].x, outputs[1].y), (2560, 0));
+        assert_eq!(outputs[1].transform, Transform::Rotate90);
+
+        let niri_mock = "\
+Output \"Dell Inc. DELL U2720Q\" (DP-1)
+  Current mode: 3840x2160 @ 59.997 Hz (preferred)
+  Variable refresh rate: not supported
+  Logical position: 0, 0
+  Logical size: 2560x1440
+  Scale: 1.5
+  Transform: normal
+Output \"LG\" (DP-2)
+  Current mode: 2560x1080 @ 75.000 Hz
+  Logical position: 2560, 0
+  Scale: 1
+  Transform: 90";
+        let outputs = parse_niri(niri_mock);
+        assert_eq!((outputs[0].width, outputs[0].height), (3840, 2160));
+        assert_eq!(outputs[0].scale, 1.5);
+        assert_eq!(outputs[1].x, 2560);
+        assert_eq!(outputs[1].transform, Transform::Rotate90);
+
+        let wlr_mock = "\
+DP-1 \"Dell Inc. DELL U2720Q\"
+  Enabled: yes
+  Modes:
+    1920x1080 px, 60.000000 Hz
+    3840x2160 px, 59.997002 Hz (preferred, current)
+  Position: 0,0
+  Transform: normal
+  Scale: 2.000000
+eDP-1 \"Laptop panel\"
+  Enabled: no
+  Modes:
+    1920x1200 px, 60.000000 Hz (preferred)";
+        let outputs = parse_wlr_randr(wlr_mock);
+        assert_eq!(outputs.len(), 1, "disabled outputs must be discarded");
+        assert_eq!((outputs[0].width, outputs[0].height), (3840, 2160));
+        assert_eq!(outputs[0].scale, 2.0);
+    }
 }
@@ -1,6 +1,6 @@
 use crate::{
-    CommandExt, Config, DaemonConfig, DaemonManager, FileInfo, WallSwitchError, WallSwitchResult,
-    WallpaperBackend, detect_monitors,
+    CommandExt, Config, DaemonConfig, DaemonManager, FileInfo, Output, WallSwitchError,
+    WallSwitchResult, WallpaperBackend, assign_canvases,
 };
 use std::process::Command;
 
@@ -8,11 +8,9 @@ use std::process::Command;
 pub struct HyprlandBackend;
 
 impl WallpaperBackend for HyprlandBackend {
-    fn apply(images: &[FileInfo], config: &Config) -> WallSwitchResult<()> {
-        let monitors = detect_monitors(config)?;
-
+    fn apply(images: &[FileInfo], outputs: &[Output], config: &Config) -> WallSwitchResult<()> {
         if config.verbose {
-            println!("monitors:\n{monitors:#?}\n");
+            println!("monitors:\n{outputs:#?}\n");
         }
 
         // Define the lifecycle configuration for the hyprpaper daemon
@@ -23,21 +21,26 @@ impl WallpaperBackend for HyprlandBackend {
 
         DaemonManager::ensure_running(config, &daemon_cfg)?;
 
-        // Cycle through images and apply them to the detected monitors.
-        for (image, monitor) in images.iter().cycle().zip(&monitors) {
+        // Bind each compiled canvas to its output by name (index for unnamed monitors).
+        for (canvas, monitor) in assign_canvases(&config.monitors, outputs) {
+            let Some(image) = images.get(canvas) else {
+                continue;
+            };
+
             let path_str = image
                 .path
                 .to_str()
                 .ok_or_else(|| WallSwitchError::InvalidFilename(image.path.clone()))?;
 
             let mut wall_cmd = Command::new("hyprctl");
-            let wall_arg = format!("{monitor},{path_str}");
+            let wall_arg = format!("{},{path_str}", monitor.name);
             wall_cmd.args(["hyprpaper", "wallpaper", &wall_arg]);
 
             if config.dry_run {
                 println!("[DRY-RUN] Would execute: {:?}", wall_cmd);
             } else {
-                wall_cmd.run_with_config(config, &format!("Apply hyprpaper on {monitor}"))?;
+                wall_cmd
+                    .run_with_config(config, &format!("Apply hyprpaper on {}", monitor.name))?;
             }
         }
 
@@ -1,6 +1,6 @@
 use crate::{
-    Config, DaemonConfig, DaemonManager, FileInfo, WallSwitchError, WallSwitchResult,
-    WallpaperBackend, detect_monitors,
+    Config, DaemonConfig, DaemonManager, FileInfo, Output, WallSwitchError, WallSwitchResult,
+    WallpaperBackend, assign_canvases,
 };
 use std::process::{Command, Stdio};
 
@@ -8,11 +8,9 @@ use std::process::{Command, Stdio};
 pub struct SwaybgBackend;
 
 impl WallpaperBackend for SwaybgBackend {
-    fn apply(images: &[FileInfo], config: &Config) -> WallSwitchResult<()> {
-        let monitors = detect_monitors(config)?;
-
+    fn apply(images: &[FileInfo], outputs: &[Output], config: &Config) -> WallSwitchResult<()> {
         if config.verbose {
-            println!("monitors:\n{monitors:#?}\n");
+            println!("monitors:\n{outputs:#?}\n");
         }
 
         // Define the lifecycle configuration for the swaybg daemon.
@@ -24,10 +22,15 @@ impl WallpaperBackend for SwaybgBackend {
         DaemonManager::ensure_running(config, &daemon_cfg)?;
 
         let mut cmd = Command::new("swaybg");
-        for (image, monitor) in images.iter().cycle().zip(&monitors) {
+        // Bind each compiled canvas to its output by name (index for unnamed monitors).
+        for (canvas, monitor) in assign_canvases(&config.monitors, outputs) {
+            let Some(image) = images.get(canvas) else {
+                continue;
+            };
+
             let path_str = image.path.to_str().unwrap_or_default();
             cmd.arg("-o")
-                .arg(monitor)
+                .arg(&monitor.name)
                 .arg("-i")
                 .arg(path_str)
                 .arg("-m")
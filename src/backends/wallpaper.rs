@@ -1,20 +1,24 @@
 use crate::{
-    AwwwBackend, Colors, CommandExt, Config, Desktop, Dimension, Environment, FileInfo,
-    HyprlandBackend, Monitor,
-    Orientation::{Horizontal, Vertical},
-    ProceduralEffect, SwaybgBackend, U8Extension, WallSwitchError, WallSwitchResult,
-    detect_monitors, is_installed,
+    AwwwBackend, Colors, CommandExt, Config, CycleRecord, Desktop, Dimension, DynamicSet,
+    Environment, FileInfo, HyprlandBackend, LocalTime, Monitor, MonitorRecord, Output,
+    ProceduralEffect, Resampling, Screens, SwaybgBackend, WallSwitchError, WallSwitchResult,
+    assign_canvases, cross_fade, derive_seed, desktop_layout, detect_xfce_properties, draw_picture,
+    draw_shadow, is_installed, rand, slice_spanned, with_seed, xfce_output_name,
 };
-use image::{RgbImage, imageops::FilterType};
+use image::RgbImage;
 use rayon::prelude::*; // Required for parallel iterators
-use std::{io::Error, process::Command};
+use std::{io::Error, path::Path, process::Command};
 
 /// Core trait defining the wallpaper application logic.
 /// Follows the "Functional Core, Imperative Shell" pattern.
 pub trait WallpaperBackend {
     /// PURE FUNCTION: Only constructs the required system commands.
     /// Defaults to returning an empty vector if not overridden.
-    fn build_commands(_images: &[FileInfo], _config: &Config) -> WallSwitchResult<Vec<Command>> {
+    fn build_commands(
+        _images: &[FileInfo],
+        _outputs: &[Output],
+        _config: &Config,
+    ) -> WallSwitchResult<Vec<Command>> {
         Ok(vec![])
     }
 
@@ -22,8 +26,10 @@ pub trait WallpaperBackend {
     /// It defaults to sequentially running `build_commands`, but can be
     /// overridden by compositors that require complex state checks
     /// (e.g., Hyprland preloading, Swaybg daemon spawning).
-    fn apply(images: &[FileInfo], config: &Config) -> WallSwitchResult<()> {
-        let mut commands = Self::build_commands(images, config)?;
+    ///
+    /// `outputs` are the screens detected for this cycle (see [`Screens`]).
+    fn apply(images: &[FileInfo], outputs: &[Output], config: &Config) -> WallSwitchResult<()> {
+        let mut commands = Self::build_commands(images, outputs, config)?;
         for cmd in commands.iter_mut() {
             let program_name = cmd.get_program().to_string_lossy().to_string();
             // Using the new CommandExt trait for unified execution
@@ -34,21 +40,130 @@ pub trait WallpaperBackend {
 }
 
 /// Set desktop wallpaper based on the detected Desktop Environment.
+///
+/// Each monitor draws its own effect (or the global one, see [`Monitor::resolve_effect`]),
+/// and the returned [`CycleRecord`] describes exactly what was displayed. Every monitor
+/// renders from a stream derived from `seed`, so the record can be reproduced.
+/// Canvases are compiled for, and applied to, the `screens` detected for the cycle.
 pub fn set_wallpaper(
     images: &[FileInfo],
+    screens: &Screens,
+    seed: u64,
+    config: &Config,
+    env: &Environment,
+) -> WallSwitchResult<CycleRecord> {
+    let effects: Vec<ProceduralEffect> = config
+        .monitors
+        .iter()
+        .map(|monitor| monitor.resolve_effect(config))
+        .collect();
+
+    let applied = apply_wallpapers(images, screens, &effects, seed, config, env)?;
+
+    let records = get_partitions_iter(images, &config.monitors, config.span)
+        .zip(&config.monitors)
+        .zip(effects.into_iter().zip(applied.presets))
+        .enumerate()
+        .map(
+            |(index, ((partition, monitor), (effect, preset)))| MonitorRecord {
+                monitor: index,
+                output: monitor.output.clone(),
+                images: partition.iter().map(|f| f.path.clone()).collect(),
+                base: monitor.base,
+                effect,
+                preset,
+            },
+        )
+        .collect();
+
+    Ok(CycleRecord {
+        backend: applied.backend.to_string(),
+        seed: Some(seed),
+        ..CycleRecord::new(records)
+    })
+}
+
+/// Re-applies a previously recorded wallpaper set with the same images and effects.
+///
+/// Recorded seeds re-render the overlays and generated bases identically. If the monitor
+/// layout changed since the record was made, the images are re-partitioned over the
+/// current monitors and missing effects default to none.
+pub fn restore_wallpaper(
+    record: &CycleRecord,
     config: &Config,
     env: &Environment,
 ) -> WallSwitchResult<()> {
+    let mut config = config.clone();
+    for (monitor, recorded) in config.monitors.iter_mut().zip(&record.monitors) {
+        monitor.base = recorded.base;
+    }
+
+    let seed = record.seed.unwrap_or_else(rand);
+    let screens = Screens::detect(&config);
+    apply_wallpapers(
+        &record.images(),
+        &screens,
+        &record.effects(),
+        seed,
+        &config,
+        env,
+    )?;
+    Ok(())
+}
+
+/// Outcome of [`apply_wallpapers`].
+struct AppliedSet {
+    /// Tool that displayed the compiled canvases.
+    backend: &'static str,
+    /// Preset rendered on each monitor, in monitor order (`None` if not rendered).
+    presets: Vec<Option<String>>,
+}
+
+/// Compiles the per-monitor canvases with the given concrete effects and hands them to the backend.
+fn apply_wallpapers(
+    images: &[FileInfo],
+    screens: &Screens,
+    effects: &[ProceduralEffect],
+    seed: u64,
+    config: &Config,
+    env: &Environment,
+) -> WallSwitchResult<AppliedSet> {
     // We ALWAYS compile wallpapers for all monitors.
     // This guarantees that:
     // 1. The output files are always lossless, highly compatible `.png` files in `/tmp`.
     // 2. Images are perfectly pre-cropped and pre-scaled to the native resolution of each monitor.
     // 3. The file paths passed to the backends are stable (/tmp/wallswitch_monitor_X.png),
     //    preventing VRAM leaks and file format errors (like WebP/AVIF unsupported by hyprpaper).
-    let compiled_images = compile_wallpapers_for_monitors(images, config, env)?;
+    let (compiled_images, presets) =
+        compile_wallpapers_for_monitors(images, &screens.monitors, effects, seed, config, env)?;
+
+    // 2. Dispatch to the appropriate backend (the stream after the last monitor drives transitions)
+    let stream = screens.monitors.len() as u64;
+    let backend = with_seed(derive_seed(seed, stream), || {
+        dispatch_to_backend(&compiled_images, screens, config)
+    })?;
+
+    Ok(AppliedSet { backend, presets })
+}
 
-    // 2. Dispatch to the appropriate backend using the compiled single-image-per-monitor files
-    match config.desktop {
+/// Hands the compiled single-image-per-monitor files to the backend of the desktop.
+///
+/// Returns the name of the tool that displayed them.
+fn dispatch_to_backend(
+    compiled_images: &[FileInfo],
+    screens: &Screens,
+    config: &Config,
+) -> WallSwitchResult<&'static str> {
+    let (monitors, outputs) = (&screens.monitors, &screens.outputs);
+
+    // Wayland tools set each output by name: nothing can be applied without them
+    if config.desktop.is_wayland() && outputs.is_empty() {
+        return Err(WallSwitchError::NoMonitors(
+            "any system tool (X11/Wayland/DRM)".to_string(),
+        ));
+    }
+
+    let backend = match config.desktop {
         Desktop::Gnome => {
             if config.dry_run {
                 println!(
@@ -56,7 +171,7 @@ pub fn set_wallpaper(
                 );
             } else {
                 // Memory optimized: Sequential loading to keep peak RSS low.
-                let final_wallpaper = assemble_final_wallpaper(&compiled_images, config)?;
+                let final_wallpaper = assemble_final_wallpaper(compiled_images, monitors, config)?;
                 final_wallpaper
                     .save(&config.wallpaper)
                     .map_err(|e| WallSwitchError::Io(Error::other(e)))?;
@@ -66,18 +181,25 @@ pub fn set_wallpaper(
                 }
             }
 
-            GnomeBackend::apply(&compiled_images, config)?;
+            GnomeBackend::apply(compiled_images, outputs, config)?;
+            "gsettings"
         }
 
-        Desktop::Xfce => XfceBackend::apply(&compiled_images, config)?,
+        Desktop::Xfce => {
+            XfceBackend::apply(compiled_images, outputs, config)?;
+            "xfconf-query"
+        }
 
         Desktop::Hyprland => {
             if is_installed("hyprpaper") {
-                HyprlandBackend::apply(&compiled_images, config)?;
+                HyprlandBackend::apply(compiled_images, outputs, config)?;
+                "hyprpaper"
             } else if is_installed("awww") {
-                AwwwBackend::apply(&compiled_images, config)?;
+                AwwwBackend::apply(compiled_images, outputs, config)?;
+                "awww"
             } else if is_installed("swaybg") {
-                SwaybgBackend::apply(&compiled_images, config)?;
+                SwaybgBackend::apply(compiled_images, outputs, config)?;
+                "swaybg"
             } else {
                 return Err(WallSwitchError::MissingWaylandTools);
             }
@@ -85,18 +207,23 @@ pub fn set_wallpaper(
 
         Desktop::Niri | Desktop::Labwc | Desktop::Mango | Desktop::Wayland => {
             if is_installed("awww") {
-                AwwwBackend::apply(&compiled_images, config)?;
+                AwwwBackend::apply(compiled_images, outputs, config)?;
+                "awww"
             } else if is_installed("swaybg") {
-                SwaybgBackend::apply(&compiled_images, config)?;
+                SwaybgBackend::apply(compiled_images, outputs, config)?;
+                "swaybg"
             } else {
                 return Err(WallSwitchError::MissingWaylandTools);
             }
         }
 
-        Desktop::Openbox => OpenboxBackend::apply(&compiled_images, config)?,
-    }
+        Desktop::Openbox => {
+            OpenboxBackend::apply(compiled_images, outputs, config)?;
+            "feh"
+        }
+    };
 
-    Ok(())
+    Ok(backend)
 }
 
 // ==============================================================================
@@ -118,7 +245,11 @@ impl WallpaperBackend for GnomeBackend {
     /// This function does not currently return an error under standard operation,
     /// but returns a [`WallSwitchResult`] to comply with the [`WallpaperBackend`] trait.
     ///
-    fn build_commands(_images: &[FileInfo], config: &Config) -> WallSwitchResult<Vec<Command>> {
+    fn build_commands(
+        _images: &[FileInfo],
+        _outputs: &[Output],
+        config: &Config,
+    ) -> WallSwitchResult<Vec<Command>> {
         let mut commands = Vec::new();
 
         // Format the absolute file path into a standard "file://" URI
@@ -153,22 +284,44 @@ impl WallpaperBackend for GnomeBackend {
 pub struct XfceBackend;
 
 impl WallpaperBackend for XfceBackend {
-    fn build_commands(images: &[FileInfo], config: &Config) -> WallSwitchResult<Vec<Command>> {
+    fn build_commands(
+        images: &[FileInfo],
+        _outputs: &[Output],
+        config: &Config,
+    ) -> WallSwitchResult<Vec<Command>> {
         let mut commands = Vec::new();
-        let monitors = detect_monitors(config)?;
+        let properties = detect_xfce_properties(config)?;
 
         if config.verbose {
-            println!("monitors:\n{monitors:#?}");
+            println!("properties:\n{properties:#?}");
         }
 
-        // Cycle through compiled single-image-per-monitor backgrounds
-        for (image, monitor) in images.iter().cycle().zip(monitors) {
+        // Bind each compiled canvas to its output by name (index for unnamed monitors)
+        let outputs: Vec<Output> = properties
+            .iter()
+            .map(|property| Output::named(xfce_output_name(property)))
+            .collect();
+
+        for (canvas, output) in assign_canvases(&config.monitors, &outputs) {
+            let Some(image) = images.get(canvas) else {
+                continue;
+            };
+            // Outputs follow `properties` one to one: without xrandr, both `.../DP-2/...`
+            // and `.../monitorDP-2/...` resolve to the same name and must each be set
+            let Some(property) = outputs
+                .iter()
+                .position(|candidate| std::ptr::eq(candidate, output))
+                .map(|index| &properties[index])
+            else {
+                continue;
+            };
+
             let mut cmd = Command::new("xfconf-query");
             cmd.args([
                 "--channel",
                 "xfce4-desktop",
                 "--property",
-                &monitor,
+                property,
                 "--create",
                 "--type",
                 "string",
@@ -186,7 +339,11 @@ impl WallpaperBackend for XfceBackend {
 pub struct OpenboxBackend;
 
 impl WallpaperBackend for OpenboxBackend {
-    fn build_commands(images: &[FileInfo], config: &Config) -> WallSwitchResult<Vec<Command>> {
+    fn build_commands(
+        images: &[FileInfo],
+        _outputs: &[Output],
+        config: &Config,
+    ) -> WallSwitchResult<Vec<Command>> {
         let mut feh_cmd = Command::new(&config.path_feh);
 
         for image in images {
@@ -201,51 +358,17 @@ impl WallpaperBackend for OpenboxBackend {
 // STRUCTURAL & MATHEMATICAL GEOMETRY COMPUTATIONS (Pure Helpers)
 // ==============================================================================
 
-struct LayoutTarget {
-    base_w: u64,
-    base_h: u64,
-    rem_w: usize,
-    rem_h: usize,
-}
-
-impl LayoutTarget {
-    fn calculate(monitor: &Monitor) -> Result<Self, std::num::TryFromIntError> {
-        let mut width = monitor.resolution.width;
-        let mut height = monitor.resolution.height;
-        let pics_per_monitor = monitor.pictures_per_monitor.to_u64();
-
-        let rem_w = (width % pics_per_monitor).try_into()?;
-        let rem_h = (height % pics_per_monitor).try_into()?;
-
-        match monitor.picture_orientation {
-            Horizontal => height /= pics_per_monitor,
-            Vertical => width /= pics_per_monitor,
-        }
-
-        Ok(Self {
-            base_w: width,
-            base_h: height,
-            rem_w,
-            rem_h,
-        })
-    }
-}
-
-/// Helper function to select and apply procedural overlays in-memory.
+/// Helper function to apply an already resolved procedural overlay in-memory.
+///
+/// Returns the name of the preset picked by the generator, if any.
 fn apply_selected_effect(
     canvas: &mut RgbImage,
     monitor: &Monitor,
     config: &Config,
+    resolved: ProceduralEffect,
     index: usize,
-) -> WallSwitchResult<()> {
-    if config.effect == ProceduralEffect::None {
-        return Ok(());
-    }
-
-    // 1. Resolve the effect once to prevent non-deterministic double-evaluation bugs
-    let resolved = config.effect.resolve();
-
-    // 2. Factory builds the resolved dynamic effect polymorphically (propagates Err if any)
+) -> WallSwitchResult<Option<String>> {
+    // Factory builds the resolved dynamic effect polymorphically (propagates Err if any)
     if let Some(renderer) = resolved.get_renderer(monitor, config)? {
         if config.verbose {
             let idx = index.to_string().bold().cyan();
@@ -257,19 +380,25 @@ fn apply_selected_effect(
 
         // Execute the render logic in-memory
         renderer.apply(canvas);
+
+        return Ok(renderer.preset_name());
     }
 
-    Ok(())
+    Ok(None)
 }
 
 /// Compiles a single monitor canvas, applies overlays, saves the output to disk, and builds its FileInfo metadata.
+///
+/// Also returns the name of the effect preset that was rendered, if any.
 fn compile_single_monitor_background(
     partition: &[FileInfo],
+    spanned: Option<&RgbImage>,
     monitor: &Monitor,
+    effect: ProceduralEffect,
     config: &Config,
     env: &Environment,
     index: usize,
-) -> WallSwitchResult<FileInfo> {
+) -> WallSwitchResult<(FileInfo, Option<String>)> {
     let cache_dir = env.get_app_cache_dir();
 
     // Ensure the cache directory exists before writing to it
@@ -278,6 +407,7 @@ fn compile_single_monitor_background(
     }
 
     let output_path = cache_dir.join(format!("wallswitch_monitor_{index}.png"));
+    let mut preset = None;
 
     if config.dry_run {
         if config.verbose {
@@ -287,13 +417,10 @@ fn compile_single_monitor_background(
             );
         }
     } else {
-        // 1. Assemble separate pictures into a single composite monitor background in-memory
-        let mut monitor_canvas = assemble_monitor_canvas(partition, monitor)?;
-
-        // 2. Overlay dynamic procedural adjustments if any are requested
-        if config.effect != ProceduralEffect::None {
-            apply_selected_effect(&mut monitor_canvas, monitor, config, index)?;
-        }
+        // 1-2. Assemble the pictures and overlay the procedural effect in-memory
+        let (monitor_canvas, rendered) =
+            render_monitor_canvas(partition, spanned, monitor, effect, config, index)?;
+        preset = rendered;
 
         // 3. Save compiled monitor canvas to disk
         monitor_canvas
@@ -306,7 +433,7 @@ fn compile_single_monitor_background(
     }
 
     // 4. Construct structural metadata representing the updated target file
-    Ok(FileInfo {
+    let compiled = FileInfo {
         path: output_path,
         size: 0,
         mtime: 0,
@@ -315,18 +442,147 @@ fn compile_single_monitor_background(
             width: monitor.resolution.width,
             height: monitor.resolution.height,
         }),
+        focal_point: None,
         is_valid: Some(true),
         number: index + 1,
         total: config.monitors.len(),
+    };
+
+    Ok((compiled, preset))
+}
+
+/// Assembles the pictures of one monitor and overlays the resolved effect, in-memory.
+///
+/// Returns the canvas together with the name of the rendered preset, if any.
+fn render_monitor_canvas(
+    partition: &[FileInfo],
+    spanned: Option<&RgbImage>,
+    monitor: &Monitor,
+    effect: ProceduralEffect,
+    config: &Config,
+    index: usize,
+) -> WallSwitchResult<(RgbImage, Option<String>)> {
+    // 1. Assemble separate pictures into a single composite monitor background in-memory,
+    //    take the monitor's slice of a spanned image, or draw the dynamic set or generated base
+    let mut monitor_canvas = if let Some(manifest) = &monitor.dynamic {
+        render_dynamic_canvas(manifest, monitor, config)?
+    } else if monitor.base.is_generated() {
+        let (width, height) = (monitor.resolution.width, monitor.resolution.height);
+        monitor
+            .base
+            .generate(width.try_into()?, height.try_into()?)?
+    } else if let Some(slice) = spanned {
+        slice.clone()
+    } else {
+        assemble_monitor_canvas(partition, monitor, config.resample)?
+    };
+
+    // 2. Overlay dynamic procedural adjustments if any are requested
+    let mut preset = None;
+    if effect != ProceduralEffect::None {
+        preset = apply_selected_effect(&mut monitor_canvas, monitor, config, effect, index)?;
+    }
+
+    Ok((monitor_canvas, preset))
+}
+
+/// Draws the frame of a dynamic set matching the current time, cross-faded with the next one.
+fn render_dynamic_canvas(
+    manifest: &Path,
+    monitor: &Monitor,
+    config: &Config,
+) -> WallSwitchResult<RgbImage> {
+    let set = DynamicSet::open(manifest)?;
+    let blend = set.select(LocalTime::now(), config.location.as_ref())?;
+    let size = (
+        monitor.resolution.width as u32,
+        monitor.resolution.height as u32,
+    );
+
+    let place = |index: usize| -> WallSwitchResult<RgbImage> {
+        let path = &set.frames[index].image;
+        let img = image::open(path).map_err(|err| WallSwitchError::CorruptImage {
+            path: path.clone(),
+            source: err,
+        })?;
+        Ok(monitor.fit.place(
+            &img,
+            size,
+            monitor.crop,
+            None,
+            monitor.fit_color,
+            config.resample,
+        ))
+    };
+
+    let current = place(blend.current)?;
+
+    // Below one level of the 8-bit scale, the next frame would not change a pixel
+    if blend.next == blend.current || blend.progress < 1.0 / 255.0 {
+        return Ok(current);
+    }
+
+    Ok(cross_fade(&current, &place(blend.next)?, blend.progress))
+}
+
+/// Renders `images` onto a single canvas of the `monitor` layout and writes it to `output`.
+///
+/// This is the cycle renderer without any backend: the desktop, the history and the
+/// cache files are left untouched. The effect is always drawn (`effects.percent` does
+/// not apply) and a meta-effect is picked with the `seed` stream,
+/// so the same inputs and seed always produce the same file. The returned record
+/// describes what was rendered.
+///
+/// # Errors
+///
+/// Returns a [`WallSwitchResult`] if an input cannot be decoded or the output cannot be written.
+pub fn render_wallpaper(
+    images: &[FileInfo],
+    monitor: &Monitor,
+    effect: ProceduralEffect,
+    seed: u64,
+    config: &Config,
+    output: &Path,
+) -> WallSwitchResult<MonitorRecord> {
+    let (effect, canvas, preset) = with_seed(seed, || {
+        let effect = effect.pick(&config.effects.weights);
+        render_monitor_canvas(images, None, monitor, effect, config, 0)
+            .map(|(canvas, preset)| (effect, canvas, preset))
+    })?;
+
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(WallSwitchError::Io)?;
+    }
+
+    canvas
+        .save(output)
+        .map_err(|e| WallSwitchError::Io(Error::other(e)))?;
+
+    Ok(MonitorRecord {
+        monitor: 0,
+        output: None,
+        images: images.iter().map(|f| f.path.clone()).collect(),
+        base: monitor.base,
+        effect,
+        preset,
     })
 }
 
 /// Pre-processes and compiles separate multi-picture composite backgrounds in parallel for each monitor.
+///
+/// `monitors` is the effective monitor list for this cycle (see [`resolve_monitors`]) and
+/// `effects` holds the concrete overlay of each monitor (missing entries mean none).
+/// Monitor `i` draws its random numbers from `derive_seed(seed, i)`, independently of
+/// the Rayon thread it runs on. Returns the compiled canvases together with the preset
+/// rendered on each of them.
 pub fn compile_wallpapers_for_monitors(
     images: &[FileInfo],
+    monitors: &[Monitor],
+    effects: &[ProceduralEffect],
+    seed: u64,
     config: &Config,
     env: &Environment,
-) -> WallSwitchResult<Vec<FileInfo>> {
+) -> WallSwitchResult<(Vec<FileInfo>, Vec<Option<String>>)> {
     if config.verbose {
         if config.dry_run {
             println!("[DRY-RUN] Would assemble multi-monitor wallpaper in pure Rust ...");
@@ -336,52 +592,84 @@ pub fn compile_wallpapers_for_monitors(
     }
 
     // 1. First, collect the partitions into a Vec so we can use Rayon's parallel iterator.
-    let partitions: Vec<&[FileInfo]> = get_partitions_iter(images, config).collect();
+    let partitions: Vec<&[FileInfo]> = get_partitions_iter(images, monitors, config.span).collect();
+
+    // A spanned image is loaded once and cut into one slice per monitor
+    let slices = match images.first() {
+        Some(image_info) if config.span && !config.dry_run => {
+            let img =
+                image::open(&image_info.path).map_err(|err| WallSwitchError::CorruptImage {
+                    path: image_info.path.clone(),
+                    source: err,
+                })?;
+            slice_spanned(&img, monitors, &config.monitor_orientation, config.resample)
+        }
+        _ => Vec::new(),
+    };
 
     // 2. Use Rayon to process the partitions in parallel.
     let compiled_files = partitions
         .into_par_iter()
-        .zip(&config.monitors)
+        .zip(monitors)
         .enumerate()
         .map(|(index, (partition, monitor))| {
-            compile_single_monitor_background(partition, monitor, config, env, index)
+            let effect = effects.get(index).copied().unwrap_or_default();
+            with_seed(derive_seed(seed, index as u64), || {
+                compile_single_monitor_background(
+                    partition,
+                    slices.get(index),
+                    monitor,
+                    effect,
+                    config,
+                    env,
+                    index,
+                )
+            })
         })
         .collect::<WallSwitchResult<Vec<_>>>()?;
 
-    Ok(compiled_files)
+    Ok(compiled_files.into_iter().unzip())
 }
 
 /// Assembles multiple sub-images into a single cohesive canvas for a given monitor in-memory.
 fn assemble_monitor_canvas(
     partition: &[FileInfo],
     monitor: &Monitor,
+    resampling: Resampling,
 ) -> WallSwitchResult<RgbImage> {
-    let mut monitor_canvas = RgbImage::new(
+    let mut monitor_canvas = RgbImage::from_pixel(
         monitor.resolution.width as u32,
         monitor.resolution.height as u32,
+        image::Rgb(monitor.gutter_color),
     );
-    let target = LayoutTarget::calculate(monitor)?;
 
-    let mut current_x = 0;
-    let mut current_y = 0;
+    // Adaptive layouts (justified) need the aspect ratio of every image up front
+    let aspects = partition
+        .iter()
+        .map(|image_info| {
+            let (width, height) = match &image_info.dimension {
+                Some(dimension) => (dimension.width, dimension.height),
+                None => image::image_dimensions(&image_info.path)
+                    .map(|(w, h)| (w.into(), h.into()))
+                    .map_err(|err| WallSwitchError::CorruptImage {
+                        path: image_info.path.clone(),
+                        source: err,
+                    })?,
+            };
+            Ok(width as f64 / height.max(1) as f64)
+        })
+        .collect::<WallSwitchResult<Vec<f64>>>()?;
 
-    for (p_idx, image_info) in partition.iter().enumerate() {
-        let mut w = target.base_w;
-        let mut h = target.base_h;
+    let cells = monitor.framed_cells(&aspects);
 
-        match monitor.picture_orientation {
-            Horizontal => {
-                if p_idx < target.rem_h {
-                    h += 1;
-                }
-            }
-            Vertical => {
-                if p_idx < target.rem_w {
-                    w += 1;
-                }
-            }
+    // Shadows first, so that no shadow falls on a neighbouring picture
+    if monitor.shadow {
+        for &cell in &cells {
+            draw_shadow(&mut monitor_canvas, cell, monitor.corner_radius);
         }
+    }
 
+    for (image_info, cell) in partition.iter().zip(cells) {
         // Memory optimization: Load, resize, and convert inside a nested block to drop
         // the heavy uncompressed DynamicImage (`img`) immediately before drawing.
         let resized = {
@@ -392,28 +680,19 @@ fn assemble_monitor_canvas(
                     source: err,
                 })?;
 
-            // Center crop and scale preserving aspect ratio
-            img.resize_to_fill(w as u32, h as u32, FilterType::Triangle)
-                .to_rgb8()
+            // Place into the cell (fill crops by centre, saliency or stored focal point)
+            monitor.fit.place(
+                &img,
+                (cell.width, cell.height),
+                monitor.crop,
+                image_info.focal_point,
+                monitor.fit_color,
+                resampling,
+            )
         };
 
         // Draw sub-image onto the monitor canvas
-        image::imageops::overlay(
-            &mut monitor_canvas,
-            &resized,
-            current_x as i64,
-            current_y as i64,
-        );
-
-        // Adjust coordinates for the next image in the layout
-        match monitor.picture_orientation {
-            Horizontal => {
-                current_y += h;
-            }
-            Vertical => {
-                current_x += w;
-            }
-        }
+        draw_picture(&mut monitor_canvas, &resized, cell, monitor.corner_radius);
     }
 
     Ok(monitor_canvas)
@@ -422,29 +701,13 @@ fn assemble_monitor_canvas(
 /// Stitches all compiled monitor canvases together to generate the final spanned multi-monitor wallpaper in-memory.
 fn assemble_final_wallpaper(
     compiled_images: &[FileInfo],
+    monitors: &[Monitor],
     config: &Config,
 ) -> WallSwitchResult<RgbImage> {
-    let mut total_w = 0;
-    let mut total_h = 0;
-
-    for monitor in &config.monitors {
-        match config.monitor_orientation {
-            Horizontal => {
-                total_w += monitor.resolution.width;
-                total_h = total_h.max(monitor.resolution.height);
-            }
-            Vertical => {
-                total_w = total_w.max(monitor.resolution.width);
-                total_h += monitor.resolution.height;
-            }
-        }
-    }
-
-    let mut final_canvas = RgbImage::new(total_w as u32, total_h as u32);
-    let mut current_x = 0;
-    let mut current_y = 0;
+    let (corners, size) = desktop_layout(monitors, &config.monitor_orientation);
+    let mut final_canvas = RgbImage::new(size.width.try_into()?, size.height.try_into()?);
 
-    for (idx, img_info) in compiled_images.iter().enumerate() {
+    for (img_info, corner) in compiled_images.iter().zip(corners) {
         // Load, convert to RGB8, draw, and immediately drop to keep memory consumption low
         let img = image::open(&img_info.path)
             .map_err(|e| {
@@ -454,28 +717,91 @@ fn assemble_final_wallpaper(
             })?
             .to_rgb8();
 
-        image::imageops::overlay(&mut final_canvas, &img, current_x as i64, current_y as i64);
-
-        match config.monitor_orientation {
-            Horizontal => {
-                current_x += config.monitors[idx].resolution.width;
-            }
-            Vertical => {
-                current_y += config.monitors[idx].resolution.height;
-            }
-        }
+        image::imageops::overlay(&mut final_canvas, &img, corner.x, corner.y);
     }
 
     Ok(final_canvas)
 }
 
+/// Splits the cycle images into the pictures of each monitor, in monitor order.
+///
+/// A spanned image is shared by every monitor that shows photos.
 fn get_partitions_iter<'a>(
     mut images: &'a [FileInfo],
-    config: &'a Config,
+    monitors: &'a [Monitor],
+    span: bool,
 ) -> impl Iterator<Item = &'a [FileInfo]> {
-    config.monitors.iter().map(move |monitor| {
-        let (head, tail) = images.split_at(monitor.pictures_per_monitor.into());
+    monitors.iter().map(move |monitor| {
+        if span {
+            let count = monitor.pictures_needed().min(images.len()).min(1);
+            return &images[..count];
+        }
+
+        let count = monitor.pictures_needed().min(images.len());
+        let (head, tail) = images.split_at(count);
         images = tail;
         head
     })
 }
+
+//----------------------------------------------------------------------------//
+//                                   Tests                                    //
+//----------------------------------------------------------------------------//
+
+#[cfg(test)]
+mod tests_wallpaper {
+    use super::*;
+    use crate::Orientation;
+    use image::Rgb;
+
+    #[test]
+    fn test_render_wallpaper_layout() -> WallSwitchResult<()> {
+        let dir = std::env::temp_dir().join(format!("wallswitch_render_{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+
+        let colors = [Rgb([255, 0, 0]), Rgb([0, 0, 255])];
+        let images: Vec<FileInfo> = colors
+            .iter()
+            .enumerate()
+            .map(|(index, color)| {
+                let path = dir.join(format!("input_{index}.png"));
+                RgbImage::from_pixel(40, 40, *color).save(&path)?;
+                Ok(FileInfo {
+                    path,
+                    ..FileInfo::default()
+                })
+            })
+            .collect::<Result<_, image::ImageError>>()?;
+
+        let monitor = Monitor {
+            picture_orientation: Orientation::Vertical,
+            pictures_per_monitor: 2,
+            resolution: Dimension {
+                width: 64,
+                height: 16,
+            },
+            ..Monitor::default()
+        };
+
+        let output = dir.join("nested").join("render.png");
+        let config = Config::default();
+        let record = render_wallpaper(
+            &images,
+            &monitor,
+            ProceduralEffect::None,
+            7,
+            &config,
+            &output,
+        )?;
+
+        let rendered = image::open(&output)?.to_rgb8();
+        std::fs::remove_dir_all(&dir)?;
+
+        assert_eq!(rendered.dimensions(), (64, 16));
+        assert_eq!(rendered.get_pixel(0, 8), &colors[0]);
+        assert_eq!(rendered.get_pixel(63, 8), &colors[1]);
+        assert_eq!(record.images.len(), 2);
+        assert_eq!(record.effect, ProceduralEffect::None);
+        Ok(())
+    }
+}
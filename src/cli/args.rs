@@ -1,12 +1,16 @@
-use crate::{Colors, Environment, Orientation, ProceduralEffect, SortCriteria, get_config_path};
+use crate::{
+    CanvasBase, Colors, ControlCommand, CropMode, Dimension, Environment, FitMode, Layout,
+    Orientation, ProceduralEffect, ResampleFilter, SortCriteria, get_config_path,
+};
 use clap::{
-    CommandFactory, Parser,
+    Args, CommandFactory, Parser, Subcommand,
     builder::{
         BoolishValueParser,
         styling::{AnsiColor, Effects, Styles},
     },
 }; // command-line arguments
 use clap_complete::{Generator, Shell, generate};
+use std::path::PathBuf;
 
 /// Custom Clap styling to mimic a beautiful colored help menu.
 fn get_styles() -> Styles {
@@ -74,6 +78,30 @@ fn get_after_help() -> String {
             "# Set 3 different wallpapers per monitor (Gnome desktop only)",
             "wallswitch --pictures-per-monitor 3",
         ),
+        (
+            "# Keep the subject of each photo in view instead of cropping the centre",
+            "wallswitch --crop smart",
+        ),
+        (
+            "# Show whole panoramas and phone photos over a blurred copy of themselves",
+            "wallswitch --fit blur",
+        ),
+        (
+            "# Arrange 6 pictures per monitor in a 3x2 grid",
+            "wallswitch --pictures-per-monitor 6 --layout grid --columns 3",
+        ),
+        (
+            "# Span one panorama across all monitors",
+            "wallswitch --span",
+        ),
+        (
+            "# Downscale large photos with Lanczos in linear light, then sharpen them",
+            "wallswitch --filter lanczos3 --linear-light --sharpen 0.8",
+        ),
+        (
+            "# Keep panoramas off portrait monitors: crop at most 25% of any image",
+            "wallswitch --max-crop 25",
+        ),
         (
             "# Filter images by dimension (min 1080px) and file size (max 5MB)",
             "wallswitch --min-dimension 1080 --max-size 5242880",
@@ -90,10 +118,26 @@ fn get_after_help() -> String {
             "# Apply random fractal overlays [julia, mandelbrot]",
             "wallswitch --effect fractal",
         ),
+        (
+            "# Draw a random overlay in only 25% of the cycles (biased by the config weights)",
+            "wallswitch --effect random --effects-percent 25",
+        ),
         (
             "# Apply randomized procedural overlays (fractal, star, aurora) on wallpapers",
             "wallswitch --effect random",
         ),
+        (
+            "# Pure procedural wallpapers: Nova fractals on a neon gradient, no photos needed",
+            "wallswitch --base gradient --effect nova",
+        ),
+        (
+            "# Follow the time of day with a dynamic wallpaper set",
+            "wallswitch --dynamic ~/Pictures/Mojave/dynamic.json --interval 600",
+        ),
+        (
+            "# Use the \"night\" profile of the config file",
+            "wallswitch --profile night",
+        ),
         (
             "# Dry run mode to see what would be executed without applying changes",
             "wallswitch --dry-run --verbose",
@@ -122,6 +166,30 @@ fn get_after_help() -> String {
             "# Limit CPU processing to 20% of total logical cores during rendering",
             "wallswitch --max-threads-percent 20",
         ),
+        (
+            "# Show the wallpaper history and bring back the set from two switches ago",
+            "wallswitch --history && wallswitch --previous 2",
+        ),
+        (
+            "# Render a reproducible set of images and overlays",
+            "wallswitch --once --effect random --seed 42",
+        ),
+        (
+            "# Run as a daemon controlled through $XDG_RUNTIME_DIR/wallswitch.sock",
+            "wallswitch --interval 900 daemon",
+        ),
+        (
+            "# Skip to the next wallpaper from a compositor hotkey",
+            "wallswitch ctl next",
+        ),
+        (
+            "# Change the interval of the running daemon to 10 minutes",
+            "wallswitch ctl set-interval 600",
+        ),
+        (
+            "# Render a lock-screen background to a file without touching the desktop",
+            "wallswitch render --input a.jpg --input b.jpg --size 2560x1440 --effect julia --output lock.png",
+        ),
     ];
 
     // 5. Iterate over the list, applying colors centrally and idiomatically
@@ -183,6 +251,68 @@ pub struct Arguments {
     )]
     pub max_size: Option<u64>,
 
+    /// Set the canvas of every monitor: source photos or a generated base.
+    ///
+    /// Generated bases (solid, gradient, dark) need no images, so the overlay
+    /// effect becomes the whole wallpaper.
+    #[arg(long("base"), value_enum, required = false)]
+    pub base: Option<CanvasBase>,
+
+    /// Show a dynamic wallpaper set (JSON manifest of time or sun keyed frames) on every monitor.
+    ///
+    /// The frame matching the time of day is cross-faded with the next one each cycle.
+    #[arg(long("dynamic"), value_name = "MANIFEST", required = false)]
+    pub dynamic: Option<PathBuf>,
+
+    /// Set how every monitor crops photos to fit: center or smart.
+    ///
+    /// Smart crop slides the window to the most detailed region (edge energy
+    /// and entropy), so portraits keep their heads and landscapes their horizon.
+    #[arg(long("crop"), value_enum, required = false)]
+    pub crop: Option<CropMode>,
+
+    /// Set how every monitor places photos into their slot.
+    ///
+    /// Fill crops to the slot; fit and blur keep the whole photo (panoramas,
+    /// phone photos) over colour bars or a blurred copy of itself.
+    #[arg(long("fit"), value_enum, required = false)]
+    pub fit: Option<FitMode>,
+
+    /// Set how every monitor arranges its pictures.
+    ///
+    /// Strips stack the pictures along the picture orientation; grid, hero (one
+    /// large picture plus thumbnails) and justified (rows packed by aspect ratio)
+    /// stay usable with many pictures per monitor.
+    #[arg(long("layout"), value_enum, required = false)]
+    pub layout: Option<Layout>,
+
+    /// Set the number of columns of the grid layout on every monitor.
+    #[arg(
+        long("columns"),
+        value_name = "COLUMNS",
+        required = false,
+        value_parser = clap::value_parser!(u8).range(1..),
+    )]
+    pub columns: Option<u8>,
+
+    /// Set the interpolation filter used to scale photos.
+    ///
+    /// Lanczos3 and catmullrom keep downscaled high resolution photos sharp;
+    /// triangle (the default) is the fastest smooth filter.
+    #[arg(long("filter"), value_enum, required = false)]
+    pub filter: Option<ResampleFilter>,
+
+    /// Toggle resampling photos in linear light instead of sRGB.
+    ///
+    /// Keeps fine bright details (stars, foliage) from darkening when large
+    /// photos are downscaled, at the cost of more memory.
+    #[arg(long("linear-light"), default_value_t = false)]
+    pub linear_light: bool,
+
+    /// Sharpen downscaled photos with an unsharp mask of this radius (0 to 10 pixels).
+    #[arg(long("sharpen"), value_name = "SIGMA", required = false)]
+    pub sharpen: Option<f32>,
+
     /// Read the configuration file and exit the program.
     #[arg(short('c'), long("config"), default_value_t = false)]
     pub config: bool,
@@ -252,6 +382,30 @@ pub struct Arguments {
     )]
     pub effects_max_iterations: Option<u32>,
 
+    /// Set the chance (in percent) that an overlay is drawn in each cycle [default: 100].
+    ///
+    /// Which effect is drawn follows the `weights` table of the config file.
+    #[arg(
+        long("effects-percent"),
+        value_name = "PERCENT",
+        required = false,
+        value_parser = clap::value_parser!(u8).range(0..=100),
+    )]
+    pub effects_percent: Option<u8>,
+
+    /// Skip images that would lose more than PERCENT of their area to the crop.
+    ///
+    /// Turns on aspect matching (`match_aspect`): each monitor slot gets the candidate
+    /// whose aspect ratio fits it best, and a badly fitting image is only used when
+    /// no candidate is within the limit.
+    #[arg(
+        long("max-crop"),
+        value_name = "PERCENT",
+        required = false,
+        value_parser = clap::value_parser!(u8).range(0..=100),
+    )]
+    pub max_crop: Option<u8>,
+
     /**
     Generate shell completions and exit the program.
 
@@ -330,6 +484,10 @@ pub struct Arguments {
     #[arg(short('g'), long("generate"), value_enum)]
     pub generator: Option<Shell>,
 
+    /// Show the applied wallpaper sets (time, monitor, effect and images) and exit.
+    #[arg(long("history"), default_value_t = false)]
+    pub history: bool,
+
     /// Set the interval (in seconds) between each wallpaper displayed.
     ///
     /// Default value: interval = 30 * 60 = 1800 seconds (30 minutes).
@@ -390,10 +548,38 @@ pub struct Arguments {
     )]
     pub pictures_per_monitor: Option<u8>,
 
+    /// Activate a named profile of the config file (directories, filters, effect, interval).
+    ///
+    /// The running daemon switches profiles with `wallswitch ctl profile NAME`.
+    #[arg(long("profile"), value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Re-apply the wallpaper set applied N cycles ago and exit.
+    ///
+    /// `--previous 1` restores the set shown before the current one.
+    #[arg(long("previous"), value_name = "N")]
+    pub previous: Option<usize>,
+
+    /// Seed the random generator to make cycles reproducible.
+    ///
+    /// Each cycle derives its own seed from SEED and the number of cycles run so far,
+    /// so the same seed, image collection and state produce the same selection,
+    /// effects and transitions. The seed of every cycle is shown by `--history`.
+    #[arg(long("seed"), value_name = "SEED")]
+    pub seed: Option<u64>,
+
     /// Sort the images found.
     #[arg(short('s'), long("sort"), default_value_t = false)]
     pub sort: bool,
 
+    /// Toggle spanning one image across all monitors.
+    ///
+    /// The image is laid out over the physical desktop: set `physical_size`,
+    /// `bezel` and `offset` (millimetres) on the monitors of the config file so
+    /// that lines continue straight across the bezels.
+    #[arg(long("span"), default_value_t = false)]
+    pub span: bool,
+
     /// Run without applying the wallpapers (simulation mode).
     #[arg(short('r'), long("dry-run"), default_value_t = false)]
     pub dry_run: bool,
@@ -441,6 +627,95 @@ pub struct Arguments {
     /// Show pid numbers of previous running program.
     #[arg(short('v'), long("verbose"), default_value_t = false)]
     pub verbose: bool,
+
+    /// Optional subcommand (daemon mode and its control client).
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Subcommands that change how the program runs.
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Run the wallpaper loop as a daemon controlled through a Unix socket.
+    ///
+    /// The socket is created at `$XDG_RUNTIME_DIR/wallswitch.sock`.
+    Daemon,
+
+    /// Send a control command to the running daemon and print its reply.
+    Ctl {
+        #[command(subcommand)]
+        command: ControlCommand,
+    },
+
+    /// Compose the given images into a single wallpaper file and exit.
+    ///
+    /// Uses the same layout and overlay renderer as a normal cycle, but never
+    /// touches the desktop, the history or the configuration file.
+    Render(RenderArgs),
+}
+
+/// Options of the `render` subcommand.
+#[derive(Args, Debug, Clone)]
+pub struct RenderArgs {
+    /// Source image placed on the canvas (repeat for several images, in order).
+    #[arg(long("input"), value_name = "IMAGE", required_unless_present = "base")]
+    pub inputs: Vec<PathBuf>,
+
+    /// Draw on a generated base instead of the input images.
+    #[arg(long("base"), value_enum, conflicts_with = "inputs")]
+    pub base: Option<CanvasBase>,
+
+    /// Size of the rendered canvas: "widthxheight".
+    #[arg(long("size"), value_name = "WIDTHxHEIGHT", default_value = "3840x2160")]
+    pub size: Dimension,
+
+    /// Split the canvas into horizontal strips (Horizontal) or vertical columns (Vertical).
+    #[arg(long("orientation"), default_value = "Horizontal")]
+    pub orientation: Orientation,
+
+    /// How the input images are cropped to their slot.
+    #[arg(long("crop"), value_enum, default_value = "center")]
+    pub crop: CropMode,
+
+    /// How the input images are placed into their slot.
+    #[arg(long("fit"), value_enum, default_value = "fill")]
+    pub fit: FitMode,
+
+    /// How the input images are arranged on the canvas.
+    #[arg(long("layout"), value_enum, default_value = "strips")]
+    pub layout: Layout,
+
+    /// Number of columns of the grid layout [default: as square as possible].
+    #[arg(long("columns"), value_parser = clap::value_parser!(u8).range(1..))]
+    pub columns: Option<u8>,
+
+    /// Space between neighbouring images, in pixels.
+    #[arg(long("gutter"), value_name = "PIXELS", default_value_t = 0)]
+    pub gutter: u32,
+
+    /// Space between the images and the border of the canvas, in pixels.
+    #[arg(long("margin"), value_name = "PIXELS", default_value_t = 0)]
+    pub margin: u32,
+
+    /// Radius of the rounded corners of each image, in pixels.
+    #[arg(long("corner-radius"), value_name = "PIXELS", default_value_t = 0)]
+    pub corner_radius: u32,
+
+    /// Draw a soft drop shadow under each image.
+    #[arg(long("shadow"), default_value_t = false)]
+    pub shadow: bool,
+
+    /// Procedural overlay rendered on top of the images [default: effect of the config file].
+    #[arg(long("effect"), value_enum)]
+    pub effect: Option<ProceduralEffect>,
+
+    /// Seed of the random generator [default: seed of the config file, or a fresh one].
+    #[arg(long("seed"), value_name = "SEED")]
+    pub seed: Option<u64>,
+
+    /// Output file; the format follows the extension (png, jpg, webp, ...).
+    #[arg(long("output"), value_name = "FILE")]
+    pub output: PathBuf,
 }
 
 impl Arguments {
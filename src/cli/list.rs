@@ -146,3 +146,64 @@ pub fn list_all_images(mut images: Vec<FileInfo>, criteria: SortCriteria) -> Wal
     println!("\nTotal images found: {}", total.to_string().green().bold());
     Ok(())
 }
+
+/// Displays the applied wallpaper sets, oldest first.
+///
+/// Each set is labelled with the number of cycles back it lies, which is the
+/// value to pass to `--previous` (or `ctl previous`) to bring it back.
+pub fn list_history(state: &State) {
+    let total = state.history.len();
+    let width = total.saturating_sub(1).to_string().len();
+
+    println!("Wallpaper history ({}):", "newest last".yellow().bold());
+
+    for (index, record) in state.history.iter().enumerate() {
+        let back = total - index - 1;
+        let seed = record
+            .seed
+            .map(|seed| format!(", seed {seed}"))
+            .unwrap_or_default();
+
+        // Pad before colouring: escape codes would count towards the width
+        println!(
+            "\n[{}] {} via {}{seed}",
+            format!("{back:>width$}").green().bold(),
+            record.date_time().blue(),
+            record.backend
+        );
+
+        for monitor in &record.monitors {
+            let output = monitor
+                .output
+                .as_deref()
+                .map(|name| format!(" ({name})"))
+                .unwrap_or_default();
+
+            let preset = monitor
+                .preset
+                .as_deref()
+                .map(|name| format!(" ({name})"))
+                .unwrap_or_default();
+
+            let base = if monitor.base.is_generated() {
+                format!(", base: {}", monitor.base.get_name())
+            } else {
+                String::new()
+            };
+
+            println!(
+                "  monitor {}{output}{base}, effect: {}{preset}",
+                monitor.monitor,
+                monitor.effect.get_name()
+            );
+            for path in &monitor.images {
+                println!("    {}", path.display());
+            }
+        }
+    }
+
+    println!(
+        "\nTotal sets recorded: {}",
+        total.to_string().green().bold()
+    );
+}
@@ -0,0 +1,341 @@
+//! Physical monitor geometry and bezel-compensated slicing of spanned images.
+//!
+//! A spanned image is laid out over the *physical* desktop, in millimetres,
+//! not over the concatenated pixel canvases. Each monitor occupies its real
+//! size (`physical_size`, or its resolution at the pixel pitch of the others),
+//! monitors are separated by their `bezel` gap, and `offset` shifts a monitor
+//! across the stacking axis (a screen mounted lower than its neighbour):
+//!
+//! ```text
+//!  +--------+ bezel +------------+
+//!  |   0    |       |            |
+//!  +--------+       |     1      |  <- offset: 1 sits 40 mm lower
+//!                   +------------+
+//! ```
+//!
+//! Monitors with a `position` (configured or detected) keep their place on the
+//! desktop instead, so L-shaped setups span correctly too.
+//!
+//! The image covers the bounding box of all monitors; the parts behind the
+//! bezels are cut away, so straight lines continue straight across screens.
+
+use crate::{CropWindow, Monitor, Orientation, Position, Resampling, measure_axis};
+use image::{DynamicImage, RgbImage};
+
+/// Millimetres per inch.
+const MM_PER_INCH: f64 = 25.4;
+
+/// Pixel density assumed when no monitor has a `physical_size` (pixels per inch).
+const DEFAULT_DPI: f64 = 96.0;
+
+/// Rectangle of the physical desktop, in millimetres.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhysicalRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Monitor {
+    /// Size of one pixel (millimetres per pixel) from the `physical_size` of this monitor.
+    fn pixel_pitch(&self) -> Option<f64> {
+        let size = self.physical_size.as_ref()?;
+        (size.width > 0 && self.resolution.width > 0)
+            .then(|| size.width as f64 / self.resolution.width as f64)
+    }
+}
+
+/// Places every monitor on the physical desktop.
+///
+/// Monitors without a `physical_size` take the pixel pitch of the first monitor
+/// that has one (or 96 DPI), so a setup without any physical data lays out
+/// exactly like the pixel canvases.
+///
+/// Monitors are lined up along the `orientation` axis, unless every monitor has a
+/// `position`: then each one sits at its position (measured with the pitch of the
+/// monitors it lies beyond), moved right and down by the bezels of the monitors
+/// entirely to its left and above it.
+pub fn physical_layout(monitors: &[Monitor], orientation: &Orientation) -> Vec<PhysicalRect> {
+    let default_pitch = monitors
+        .iter()
+        .find_map(Monitor::pixel_pitch)
+        .unwrap_or(MM_PER_INCH / DEFAULT_DPI);
+
+    let sizes: Vec<(f64, f64)> = monitors
+        .iter()
+        .map(|monitor| match &monitor.physical_size {
+            Some(size) if size.width > 0 && size.height > 0 => {
+                (size.width as f64, size.height as f64)
+            }
+            _ => (
+                monitor.resolution.width as f64 * default_pitch,
+                monitor.resolution.height as f64 * default_pitch,
+            ),
+        })
+        .collect();
+
+    let corners: Vec<(f64, f64)> = match positioned_layout(monitors, &sizes, default_pitch) {
+        Some(corners) => corners,
+        None => {
+            let mut along = 0.0;
+            sizes
+                .iter()
+                .zip(monitors)
+                .map(|(&(width, height), monitor)| {
+                    let corner = match orientation {
+                        Orientation::Horizontal => (along, 0.0),
+                        Orientation::Vertical => (0.0, along),
+                    };
+                    along += f64::from(monitor.bezel)
+                        + match orientation {
+                            Orientation::Horizontal => width,
+                            Orientation::Vertical => height,
+                        };
+                    corner
+                })
+                .collect()
+        }
+    };
+
+    corners
+        .into_iter()
+        .zip(sizes)
+        .zip(monitors)
+        .map(|(((x, y), (width, height)), monitor)| {
+            let across = f64::from(monitor.offset);
+            let (x, y) = match orientation {
+                Orientation::Horizontal => (x, y + across),
+                Orientation::Vertical => (x + across, y),
+            };
+            PhysicalRect {
+                x,
+                y,
+                width,
+                height,
+            }
+        })
+        .collect()
+}
+
+/// Corners (millimetres) of monitors that all have a `position`, bezels included.
+///
+/// Pixel positions are converted with the pitch of the monitors they cross, from
+/// their physical `sizes` (millimetres), and with `default_pitch` between monitors.
+fn positioned_layout(
+    monitors: &[Monitor],
+    sizes: &[(f64, f64)],
+    default_pitch: f64,
+) -> Option<Vec<(f64, f64)>> {
+    let positions: Vec<Position> = monitors.iter().map(|m| m.position).collect::<Option<_>>()?;
+
+    // Extent (pixels) and pitch (millimetres per pixel) of every monitor along each axis
+    let span = |start: i64, pixels: u64, millimetres: f64| {
+        (
+            start,
+            start + pixels as i64,
+            millimetres / pixels.max(1) as f64,
+        )
+    };
+    let (columns, rows): (Vec<_>, Vec<_>) = positions
+        .iter()
+        .zip(monitors)
+        .zip(sizes)
+        .map(|((position, monitor), &(width, height))| {
+            (
+                span(position.x, monitor.resolution.width, width),
+                span(position.y, monitor.resolution.height, height),
+            )
+        })
+        .unzip();
+
+    let corners = positions
+        .iter()
+        .map(|corner| {
+            let mut x = measure_axis(corner.x, &columns, default_pitch);
+            let mut y = measure_axis(corner.y, &rows, default_pitch);
+
+            for (other, monitor) in positions.iter().zip(monitors) {
+                if other.x + monitor.resolution.width as i64 <= corner.x {
+                    x += f64::from(monitor.bezel);
+                }
+                if other.y + monitor.resolution.height as i64 <= corner.y {
+                    y += f64::from(monitor.bezel);
+                }
+            }
+            (x, y)
+        })
+        .collect();
+
+    Some(corners)
+}
+
+/// Returns the bounding box of all `rects` (at least 1 mm on each side).
+pub fn physical_bounds(rects: &[PhysicalRect]) -> PhysicalRect {
+    let left = rects.iter().map(|r| r.x).fold(f64::INFINITY, f64::min);
+    let top = rects.iter().map(|r| r.y).fold(f64::INFINITY, f64::min);
+    let right = rects
+        .iter()
+        .map(|r| r.x + r.width)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let bottom = rects
+        .iter()
+        .map(|r| r.y + r.height)
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    PhysicalRect {
+        x: left,
+        y: top,
+        width: (right - left).max(1.0),
+        height: (bottom - top).max(1.0),
+    }
+}
+
+/// Cuts `img` into one canvas per monitor, as seen through the physical desktop.
+///
+/// The image is centre-cropped to the aspect ratio of the bounding box of all
+/// monitors; each slice is resized to the resolution of its monitor with `resampling`.
+pub fn slice_spanned(
+    img: &DynamicImage,
+    monitors: &[Monitor],
+    orientation: &Orientation,
+    resampling: Resampling,
+) -> Vec<RgbImage> {
+    let rects = physical_layout(monitors, orientation);
+    let desktop = physical_bounds(&rects);
+    let (left, top) = (desktop.x, desktop.y);
+
+    // Target ratio in tenths of a millimetre keeps enough precision for the crop
+    let target = (
+        (desktop.width * 10.0) as u32,
+        (desktop.height * 10.0) as u32,
+    );
+    let window = CropWindow::centered((img.width(), img.height()), target);
+    let scale = f64::from(window.width) / desktop.width; // source pixels per millimetre
+
+    rects
+        .iter()
+        .zip(monitors)
+        .map(|(rect, monitor)| {
+            let x = f64::from(window.x) + (rect.x - left) * scale;
+            let y = f64::from(window.y) + (rect.y - top) * scale;
+            let x = (x.round() as u32).min(img.width() - 1);
+            let y = (y.round() as u32).min(img.height() - 1);
+            let width = ((rect.width * scale).round() as u32).clamp(1, img.width() - x);
+            let height = ((rect.height * scale).round() as u32).clamp(1, img.height() - y);
+
+            resampling.resize_exact(
+                &img.crop_imm(x, y, width, height),
+                monitor.resolution.width as u32,
+                monitor.resolution.height as u32,
+            )
+        })
+        .collect()
+}
+
+//----------------------------------------------------------------------------//
+//                                   Tests                                    //
+//----------------------------------------------------------------------------//
+
+#[cfg(test)]
+mod tests_bezel {
+    use super::*;
+    use crate::Dimension;
+    use image::Rgb;
+
+    fn monitor(width: u64, height: u64) -> Monitor {
+        Monitor {
+            resolution: Dimension { width, height },
+            ..Monitor::default()
+        }
+    }
+
+    #[test]
+    fn test_physical_layout() {
+        // Same pitch, no bezel: the physical layout matches the pixel canvases
+        let plain = physical_layout(
+            &[monitor(100, 50), monitor(100, 50)],
+            &Orientation::Horizontal,
+        );
+        assert_eq!(plain[1].x, plain[0].width);
+
+        // 20 mm bezel and a second screen mounted 40 mm lower
+        let mut right = monitor(1920, 1080);
+        right.physical_size = Some(Dimension {
+            width: 600,
+            height: 340,
+        });
+        right.offset = 40;
+        let mut left = monitor(1920, 1080);
+        left.physical_size = Some(Dimension {
+            width: 510,
+            height: 290,
+        });
+        left.bezel = 20;
+
+        let rects = physical_layout(&[left, right], &Orientation::Horizontal);
+        assert_eq!(
+            rects[1],
+            PhysicalRect {
+                x: 530.0,
+                y: 40.0,
+                width: 600.0,
+                height: 340.0
+            }
+        );
+    }
+
+    #[test]
+    fn test_positioned_layout_with_mixed_pitches() {
+        // Three 600 mm wide screens in a row; the middle one is 4K (half the pitch)
+        let screen = |width: u64, height: u64, x: i64| Monitor {
+            physical_size: Some(Dimension {
+                width: 600,
+                height: 340,
+            }),
+            position: Some(Position { x, y: 0 }),
+            ..monitor(width, height)
+        };
+        let monitors = [
+            screen(1920, 1080, 0),
+            screen(3840, 2160, 1920),
+            screen(1920, 1080, 5760),
+        ];
+
+        // Each position is measured with the pitch of the screens to its left
+        let rects = physical_layout(&monitors, &Orientation::Horizontal);
+        assert!((rects[1].x - 600.0).abs() < 1e-9, "{:?}", rects[1]);
+        assert!((rects[2].x - 1200.0).abs() < 1e-9, "{:?}", rects[2]);
+    }
+
+    #[test]
+    fn test_slice_spanned_hides_the_bezel() {
+        // Gradient panorama: red grows from left to right
+        let mut panorama = RgbImage::new(300, 50);
+        for (x, _, pixel) in panorama.enumerate_pixels_mut() {
+            *pixel = Rgb([(x * 255 / 299) as u8, 0, 0]);
+        }
+        let img = DynamicImage::ImageRgb8(panorama);
+
+        let mut left = monitor(100, 50);
+        left.bezel = 26; // About one screen width: 100 px at 96 DPI is 26.5 mm
+        let monitors = [left, monitor(100, 50)];
+
+        let slices = slice_spanned(
+            &img,
+            &monitors,
+            &Orientation::Horizontal,
+            Resampling::default(),
+        );
+        assert_eq!(slices.len(), 2);
+        assert_eq!(slices[1].dimensions(), (100, 50));
+
+        // The gap between the slices is missing from both screens
+        let end_of_left = slices[0].get_pixel(99, 25).0[0];
+        let start_of_right = slices[1].get_pixel(0, 25).0[0];
+        assert!(
+            start_of_right - end_of_left > 50,
+            "{end_of_left} -> {start_of_right}"
+        );
+    }
+}
@@ -1,13 +1,17 @@
 use crate::{
-    Arguments, AtomicWriteExt, Complex, Desktop, Environment, Monitor, Orientation,
-    ProceduralEffect, U8Extension, WallSwitchError, WallSwitchResult, get_feh_path, get_monitors,
+    Arguments, AtomicWriteExt, CanvasBase, Complex, Desktop, Directory, Environment, LocalTime,
+    Location, Monitor, Orientation, ProceduralEffect, Profile, Resampling, ScheduleRule,
+    WallSwitchError, WallSwitchResult, active_rule, get_feh_path, get_monitors,
+    next_schedule_change,
 };
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     env,
     fs::{self, File},
     io::{BufReader, BufWriter, Write},
     path::{Path, PathBuf},
+    time::Duration,
 };
 
 /// Configurable parameters and custom presets for procedural mathematical overlays.
@@ -36,6 +40,14 @@ pub struct EffectsConfig {
     /// User-defined Nova Julia presets.
     #[serde(default)]
     pub nova: Vec<CustomNovaPreset>,
+    /// Chance (in percent, 0 to 100) that an overlay is drawn at all in a cycle.
+    #[serde(default = "default_percent")]
+    pub percent: u8,
+    /// Relative weights of the effects picked by `random`, `fractal` and `polynomial`.
+    ///
+    /// Missing effects weigh 1; a weight of 0 never picks the effect.
+    #[serde(default = "default_weights")]
+    pub weights: BTreeMap<ProceduralEffect, u32>,
 }
 
 impl Default for EffectsConfig {
@@ -46,6 +58,8 @@ impl Default for EffectsConfig {
             add_presets: default_true(),
             min_iterations: default_min_iterations(),
             max_iterations: default_max_iterations(),
+            percent: default_percent(),
+            weights: default_weights(),
             julia: vec![
                 CustomFractalPreset {
                     center: Complex { re: -0.8, im: 0.18 },
@@ -124,6 +138,18 @@ fn default_max_iterations() -> u32 {
     1200
 }
 
+fn default_percent() -> u8 {
+    100
+}
+
+/// Lists every concrete effect with weight 1, so the table is easy to edit.
+fn default_weights() -> BTreeMap<ProceduralEffect, u32> {
+    ProceduralEffect::CONCRETE
+        .iter()
+        .map(|&effect| (effect, 1))
+        .collect()
+}
+
 /// A serialized custom Julia/Mandelbrot preset representing the focal point.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CustomFractalPreset {
@@ -149,12 +175,12 @@ pub struct CustomNovaPreset {
 }
 
 /// Configuration variables
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     /// Desktops: gnome, xfce, openbox, ...
     pub desktop: Desktop,
-    /// Directories containing image files
-    pub directories: Vec<PathBuf>,
+    /// Directories containing image files, as paths or with scanning options
+    pub directories: Vec<Directory>,
     /// Image file extension (identify -list format)
     pub extensions: Vec<String>,
     /// Interval (in seconds) between each wallpaper displayed
@@ -169,8 +195,26 @@ pub struct Config {
     pub max_size: u64,
     /// Monitor properties
     pub monitors: Vec<Monitor>,
+    /// Replace each monitor resolution with the native size of the detected output
+    #[serde(default = "default_true")]
+    pub detect_resolution: bool,
     /// Attach images to monitors in the Horizontal or Vertical orientation
+    ///
+    /// Ignored by the spanned image when every monitor has a `position`.
     pub monitor_orientation: Orientation,
+    /// Give each monitor slot the candidate image whose aspect ratio fits it best
+    #[serde(default)]
+    pub match_aspect: bool,
+    /// Skip images losing more than this percentage to the crop while better ones remain
+    /// (turns on `match_aspect`)
+    #[serde(default)]
+    pub max_crop: Option<u8>,
+    /// Stretch one image across all monitors, hiding the parts behind the bezels
+    #[serde(default)]
+    pub span: bool,
+    /// How photos are scaled to their slot: filter, linear light and sharpening
+    #[serde(default)]
+    pub resample: Resampling,
 
     /// Run a single wallpaper update cycle and exit
     #[serde(skip)]
@@ -180,8 +224,16 @@ pub struct Config {
     pub path_feh: PathBuf,
     /// Sort the images found
     pub sort: bool,
+    /// Fixed seed of the random generator (image order, effects, transitions).
+    ///
+    /// `None` draws a fresh seed for every cycle.
+    #[serde(default)]
+    pub seed: Option<u64>,
     /// Selected procedural overlay effect (none, fractal, star, random)
     pub effect: ProceduralEffect,
+    /// Draw procedural wallpapers on a dark base when no image is found
+    #[serde(default = "default_true")]
+    pub procedural_fallback: bool,
     /// Configurable parameters and custom presets for mathematical overlays
     #[serde(default)]
     pub effects: EffectsConfig,
@@ -205,6 +257,19 @@ pub struct Config {
     /// Limit CPU processing as a percentage (10% to 100%) of total logical cores
     pub max_threads_percent: u8,
 
+    /// Name of the active profile (`None` uses the base settings)
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// Named profiles overriding directories, filters, effect and interval
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+    /// Position used to compute sunrise and sunset for the schedule
+    #[serde(default)]
+    pub location: Option<Location>,
+    /// Time ranges overriding the profile, directories, effect and interval
+    #[serde(default)]
+    pub schedule: Vec<ScheduleRule>,
+
     /// Show intermediate runtime messages
     #[serde(skip)]
     pub verbose: bool,
@@ -226,20 +291,132 @@ impl Config {
         let mut read_default_config = false;
         let config_path = get_config_path(env)?;
 
-        let config: Config = match read_config_file(&config_path) {
+        let file_config: Config = match read_config_file(&config_path) {
             Ok(configuration) => configuration,
             Err(_) => {
                 read_default_config = true;
                 Self::default_with_env(env)
             }
+        };
+
+        // The file keeps the base settings: the profile is layered over a copy
+        let config = file_config
+            .clone()
+            .set_command_line_arguments(args)?
+            .validate_config()?
+            .write_config_file(&config_path, read_default_config)?;
+
+        match config.profile.as_deref() {
+            None => Ok(config),
+            Some(name) => file_config
+                .with_profile(name)?
+                .set_command_line_arguments(args)?
+                .validate_config(),
+        }
+    }
+
+    /// Re-reads the configuration file with `profile` and the schedule rule `rule` active.
+    ///
+    /// `None` selects the base settings. Command-line overrides still apply, and
+    /// the file is left untouched, so the switch lasts until the program exits.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`WallSwitchResult`] if the file cannot be read or parsed, the profile
+    /// is unknown or validation fails: the caller keeps its current configuration.
+    pub fn reload(
+        args: &Arguments,
+        env: &Environment,
+        profile: Option<&str>,
+        rule: Option<usize>,
+    ) -> WallSwitchResult<Self> {
+        let file_config = get_config_path(env).and_then(read_config_file)?;
+
+        let mut base = match profile {
+            Some(name) => file_config.with_profile(name)?,
+            None => file_config,
+        };
+
+        if let Some(rule) = rule.and_then(|index| base.schedule.get(index)).cloned() {
+            base = base.with_rule(&rule)?;
         }
-        .set_command_line_arguments(args)?
-        .validate_config()?
-        .write_config_file(&config_path, read_default_config)?;
+
+        let mut config = base.set_command_line_arguments(args)?.validate_config()?;
+        config.profile = profile.map(String::from);
 
         Ok(config)
     }
 
+    /// Applies the named profile over these settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WallSwitchError::InvalidValue`] if no profile has that name.
+    pub fn with_profile(self, name: &str) -> WallSwitchResult<Self> {
+        let profile =
+            self.profiles
+                .get(name)
+                .cloned()
+                .ok_or_else(|| WallSwitchError::InvalidValue {
+                    arg: "--profile".to_string(),
+                    value: name.to_string(),
+                })?;
+
+        Ok(Config {
+            profile: Some(name.to_string()),
+            ..profile.apply(self)
+        })
+    }
+
+    /// Applies a schedule rule over these settings: its profile, then its own overrides.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WallSwitchError::InvalidValue`] if the rule names an unknown profile.
+    pub fn with_rule(self, rule: &ScheduleRule) -> WallSwitchResult<Self> {
+        let config = match &rule.profile {
+            Some(name) => self.with_profile(name)?,
+            None => self,
+        };
+
+        Ok(rule.settings.apply(config))
+    }
+
+    /// Returns true if candidate images are matched to the monitor slots by aspect ratio:
+    /// `match_aspect` is set, or a `max_crop` limit asks for it.
+    pub fn matches_aspect(&self) -> bool {
+        self.match_aspect || self.max_crop.is_some()
+    }
+
+    /// Index of the schedule rule active at `now` (the first matching one).
+    pub fn active_rule(&self, now: LocalTime) -> Option<usize> {
+        active_rule(&self.schedule, now, self.location.as_ref())
+    }
+
+    /// Time until the next automatic switch: the interval, cut short when a schedule rule
+    /// starts or ends sooner.
+    pub fn time_to_next_switch(&self) -> Duration {
+        let interval = Duration::from_secs(self.interval);
+
+        // Without rules, there is no need to read the clock and the time zone
+        if self.schedule.is_empty() {
+            return interval;
+        }
+
+        next_schedule_change(&self.schedule, LocalTime::now(), self.location.as_ref())
+            .map_or(interval, |change| change.min(interval))
+    }
+
+    /// Reads the JSON configuration file as-is, or the defaults if it is missing or invalid.
+    ///
+    /// Unlike [`Config::new`], nothing is validated or written back, so this works on
+    /// headless machines (e.g. CI) without `feh` or a desktop session.
+    pub fn load_or_default(env: &Environment) -> Self {
+        get_config_path(env)
+            .and_then(read_config_file)
+            .unwrap_or_else(|_| Self::default_with_env(env))
+    }
+
     /// Initializes a default configuration aligned with the active environment context.
     pub fn default_with_env(env: &Environment) -> Self {
         // Set image extensions (identify -list format)
@@ -265,16 +442,27 @@ impl Config {
             max_size: u64::pow(1024, 3),
             directories: get_directories(env).unwrap_or_default(),
             effect: ProceduralEffect::None,
+            procedural_fallback: true,
             effects: EffectsConfig::default(),
             extensions,
             interval,
             monitors: get_monitors(2),
+            detect_resolution: true,
             monitor_orientation: Orientation::Horizontal,
+            match_aspect: false,
+            max_crop: None,
+            span: false,
+            resample: Resampling::default(),
             once: false,
             path_feh: PathBuf::from("/usr/bin/feh"),
             sort: false,
+            seed: None,
             wallpaper: get_wallpaper_path(env).unwrap_or_default(),
             dry_run: false,
+            profile: None,
+            profiles: BTreeMap::new(),
+            location: None,
+            schedule: Vec::new(),
             transition_type: "random".to_string(),
             transition_duration: 2,
             transition_fps: 60,
@@ -320,7 +508,7 @@ pub fn get_wallpaper_path(env: &Environment) -> WallSwitchResult<PathBuf> {
 }
 
 /// Discovers standard directories where user wallpapers are located.
-pub fn get_directories(env: &Environment) -> WallSwitchResult<Vec<PathBuf>> {
+pub fn get_directories(env: &Environment) -> WallSwitchResult<Vec<Directory>> {
     let home_dir = env.get_home_dir();
     let images = ["Figures", "Images", "Pictures", "Wallpapers", "Imagens"];
 
@@ -344,7 +532,7 @@ pub fn get_directories(env: &Environment) -> WallSwitchResult<Vec<PathBuf>> {
         }
     }
 
-    Ok(directories)
+    Ok(directories.into_iter().map(Directory::new).collect())
 }
 
 // Set boundary config values
@@ -364,6 +552,24 @@ impl Config {
         self.min_dimension <= value && value <= self.max_dimension
     }
 
+    /// Changes the switch interval at runtime, enforcing the same lower bound as `--interval`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WallSwitchError::AtLeastValue`] if `seconds` is below the boundary.
+    pub fn set_interval(&mut self, seconds: u64) -> WallSwitchResult<()> {
+        let boundary = config_boundary().interval;
+        if seconds < boundary {
+            return Err(WallSwitchError::AtLeastValue {
+                arg: "set-interval".to_string(),
+                value: seconds.to_string(),
+                num: boundary,
+            });
+        }
+        self.interval = seconds;
+        Ok(())
+    }
+
     /// Print Config
     pub fn print(&self) -> WallSwitchResult<()> {
         let json: String = serde_json::to_string_pretty(self)?;
@@ -410,6 +616,42 @@ impl Config {
             }
         }
 
+        if let Some(base) = args.base {
+            for monitor in &mut self.monitors {
+                monitor.base = base;
+            }
+        }
+
+        if let Some(manifest) = &args.dynamic {
+            for monitor in &mut self.monitors {
+                monitor.dynamic = Some(manifest.clone());
+            }
+        }
+
+        if let Some(crop) = args.crop {
+            for monitor in &mut self.monitors {
+                monitor.crop = crop;
+            }
+        }
+
+        if let Some(fit) = args.fit {
+            for monitor in &mut self.monitors {
+                monitor.fit = fit;
+            }
+        }
+
+        if let Some(layout) = args.layout {
+            for monitor in &mut self.monitors {
+                monitor.layout = layout;
+            }
+        }
+
+        if let Some(columns) = args.columns {
+            for monitor in &mut self.monitors {
+                monitor.columns = Some(columns);
+            }
+        }
+
         self.once = args.once;
 
         if args.dry_run {
@@ -449,14 +691,46 @@ impl Config {
             self.effects.max_iterations = effects_max_iterations;
         }
 
+        if let Some(effects_percent) = args.effects_percent {
+            self.effects.percent = effects_percent;
+        }
+
+        if let Some(max_crop) = args.max_crop {
+            self.max_crop = Some(max_crop);
+        }
+
+        if let Some(filter) = args.filter {
+            self.resample.filter = filter;
+        }
+
+        if args.linear_light {
+            self.resample.linear = !self.resample.linear;
+        }
+
+        if let Some(sharpen) = args.sharpen {
+            self.resample.sharpen = sharpen;
+        }
+
         if args.sort {
             self.sort = !self.sort;
         }
 
+        if args.span {
+            self.span = !self.span;
+        }
+
+        if let Some(seed) = args.seed {
+            self.seed = Some(seed);
+        }
+
         if let Some(max_threads_percent) = args.max_threads_percent {
             self.max_threads_percent = max_threads_percent;
         }
 
+        if let Some(profile) = &args.profile {
+            self.profile = Some(profile.clone());
+        }
+
         if args.verbose {
             self.verbose = !self.verbose;
         }
@@ -548,6 +822,74 @@ impl Config {
             });
         }
 
+        for monitor in &self.monitors {
+            if let Some(manifest) = &monitor.dynamic
+                && !manifest.is_file()
+            {
+                return Err(WallSwitchError::InvalidValue {
+                    arg: "--dynamic".to_string(),
+                    value: manifest.display().to_string(),
+                });
+            }
+        }
+
+        for monitor in &self.monitors {
+            if let Some(percent) = monitor.effect_percent
+                && percent > 100
+            {
+                return Err(WallSwitchError::AtMostValue {
+                    arg: "effect_percent".to_string(),
+                    value: percent.to_string(),
+                    num: 100,
+                });
+            }
+        }
+
+        if self.effects.percent > 100 {
+            return Err(WallSwitchError::AtMostValue {
+                arg: "--effects-percent".to_string(),
+                value: self.effects.percent.to_string(),
+                num: 100,
+            });
+        }
+
+        if let Some(max_crop) = self.max_crop
+            && max_crop > 100
+        {
+            return Err(WallSwitchError::AtMostValue {
+                arg: "--max-crop".to_string(),
+                value: max_crop.to_string(),
+                num: 100,
+            });
+        }
+
+        if !(0.0..=10.0).contains(&self.resample.sharpen) {
+            return Err(WallSwitchError::InvalidValue {
+                arg: "--sharpen".to_string(),
+                value: self.resample.sharpen.to_string(),
+            });
+        }
+
+        if let Some(location) = &self.location {
+            location.validate()?;
+        }
+
+        for rule in &self.schedule {
+            if rule.uses_sun() && self.location.is_none() {
+                return Err(WallSwitchError::MissingValue {
+                    arg: "location".to_string(),
+                });
+            }
+            if let Some(name) = &rule.profile
+                && !self.profiles.contains_key(name)
+            {
+                return Err(WallSwitchError::InvalidValue {
+                    arg: "schedule".to_string(),
+                    value: name.clone(),
+                });
+            }
+        }
+
         if !(10..=100).contains(&self.max_threads_percent) {
             return Err(WallSwitchError::InvalidValue {
                 arg: "--max-threads-percent".to_string(),
@@ -586,12 +928,35 @@ impl Config {
         Ok(self)
     }
 
+    /// Returns the configuration used when no image can be found at all.
+    ///
+    /// Every photo-based monitor gets a dark generated base, and a random overlay
+    /// is always drawn where none is configured.
+    pub fn procedural(&self) -> Self {
+        let mut config = self.clone();
+
+        for monitor in &mut config.monitors {
+            if !monitor.base.is_generated() {
+                monitor.base = CanvasBase::Dark;
+            }
+            if monitor.effect == Some(ProceduralEffect::None) {
+                monitor.effect = None;
+            }
+            monitor.effect_percent = None;
+        }
+        config.effects.percent = 100;
+
+        if config.effect == ProceduralEffect::None {
+            config.effect = ProceduralEffect::Random;
+        }
+
+        config
+    }
+
     /// Get the number of images per cycle
     pub fn get_number_of_images(&self) -> usize {
-        self.monitors
-            .iter()
-            .map(|monitor| monitor.pictures_per_monitor.to_usize())
-            .sum()
+        let needed: usize = self.monitors.iter().map(Monitor::pictures_needed).sum();
+        if self.span { needed.min(1) } else { needed }
     }
 }
 
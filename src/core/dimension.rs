@@ -1,6 +1,6 @@
-use crate::{Colors, Config, DigitWidth};
+use crate::{Colors, Config, DigitWidth, DimensionError};
 use serde::{Deserialize, Serialize};
-use std::fmt;
+use std::{fmt, str::FromStr};
 
 /// Represents the physical dimensions (width and height) of an image.
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
@@ -89,6 +89,41 @@ impl fmt::Display for Dimension {
     }
 }
 
+impl FromStr for Dimension {
+    type Err = DimensionError;
+
+    /// Parses a "widthxheight" string (e.g. "2560x1440").
+    ///
+    /// Trailing non-digit characters after the height are ignored, so mode strings
+    /// such as "1920x1080@60.00" or "1920x1080 px" are also accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (width, height) = s
+            .trim()
+            .split_once(['x', 'X'])
+            .ok_or(DimensionError::InvalidFormat)?;
+
+        let height_digits: String = height
+            .trim_start()
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect();
+
+        let width: u64 = width
+            .trim()
+            .parse()
+            .map_err(|_| DimensionError::InvalidFormat)?;
+        let height: u64 = height_digits
+            .parse()
+            .map_err(|_| DimensionError::InvalidFormat)?;
+
+        if width == 0 || height == 0 {
+            return Err(DimensionError::ZeroDimension);
+        }
+
+        Ok(Dimension { width, height })
+    }
+}
+
 //----------------------------------------------------------------------------//
 //                                   Tests                                    //
 //----------------------------------------------------------------------------//
@@ -149,6 +184,19 @@ mod tests_dimension {
         assert!(formatted.contains("width: 120"));
     }
 
+    #[test]
+    fn test_from_str() {
+        let dim: Dimension = "2560x1440".parse().unwrap();
+        assert_eq!(dim.width, 2560);
+        assert_eq!(dim.height, 1440);
+
+        let mode: Dimension = "1920x1080@60.00000".parse().unwrap();
+        assert_eq!(mode.height, 1080);
+
+        assert!("1920".parse::<Dimension>().is_err());
+        assert!("0x1080".parse::<Dimension>().is_err());
+    }
+
     // Example of testing behavior with a Config instance.
     // Replace this structure initialization with your actual Config creation pattern.
     #[test]
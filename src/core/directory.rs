@@ -0,0 +1,488 @@
+//! Image directories and their scanning options.
+//!
+//! An entry of `directories` is either a plain path, scanned recursively as
+//! before, or an object with per-directory options:
+//!
+//! ```json
+//! "directories": [
+//!   "/home/user/Pictures",
+//!   {
+//!     "path": "/mnt/nas/wallpapers",
+//!     "max_depth": 3,
+//!     "exclude": ["**/thumbnails/**", "**/.cache/**", "*_small.*"],
+//!     "hidden": false
+//!   },
+//!   { "path": "/home/user/Favourites", "weight": 10 }
+//! ]
+//! ```
+//!
+//! Glob patterns are matched against the path relative to the directory
+//! (`*` and `?` stop at `/`, `**` crosses directories); patterns without a `/`
+//! only match the file name.
+//!
+//! Weights are given to collections of images, not to single images: each
+//! directory is a collection of its own, unless it names a shared `collection`.
+//! A cycle first draws a collection by weight, then an image of that
+//! collection, so a small curated folder is not drowned by a huge archive.
+
+use crate::{FileInfo, RandomExt, get_weighted_index};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// An image directory with its scanning options.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "DirectoryEntry", into = "DirectoryEntry")]
+pub struct Directory {
+    /// Root of the scan.
+    pub path: PathBuf,
+    /// Descend into subdirectories [default: true].
+    pub recursive: bool,
+    /// Maximum depth below `path` (1 = files of `path` only) [default: unlimited].
+    pub max_depth: Option<usize>,
+    /// Follow symbolic links to files and directories [default: false].
+    pub follow_links: bool,
+    /// Only keep files matching one of these globs [default: every file].
+    pub include: Vec<String>,
+    /// Skip files and directories matching one of these globs.
+    pub exclude: Vec<String>,
+    /// Scan hidden files and directories (names starting with '.') [default: true].
+    pub hidden: bool,
+    /// Relative weight of the collection of this directory [default: 1].
+    ///
+    /// A weight of 0 only uses its images once every weighted collection is exhausted.
+    pub weight: u32,
+    /// Name of the collection shared with other directories (their weights add up).
+    pub collection: Option<String>,
+}
+
+impl Directory {
+    /// Creates a directory scanned with the default options.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Directory {
+            path: path.into(),
+            recursive: true,
+            max_depth: None,
+            follow_links: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            hidden: true,
+            weight: 1,
+            collection: None,
+        }
+    }
+
+    /// Returns true if the directory changes the uniform selection of images.
+    pub fn is_weighted(&self) -> bool {
+        self.weight != 1 || self.collection.is_some()
+    }
+
+    /// Name of the collection this directory belongs to (its path if unnamed).
+    pub fn collection_name(&self) -> String {
+        match &self.collection {
+            Some(name) => name.clone(),
+            None => self.path.display().to_string(),
+        }
+    }
+
+    /// Depth limit passed to the directory walker.
+    pub fn depth_limit(&self) -> usize {
+        let depth = self.max_depth.unwrap_or(usize::MAX);
+        if self.recursive { depth } else { depth.min(1) }
+    }
+
+    /// Returns true if the directory `relative` to `path` should not be descended into.
+    pub fn prunes(&self, relative: &Path) -> bool {
+        let relative = normalize(relative);
+        let name = relative.rsplit('/').next().unwrap_or_default();
+
+        (!self.hidden && name.starts_with('.'))
+            || self
+                .exclude
+                .iter()
+                .any(|pattern| matches_path(pattern, &format!("{relative}/")))
+    }
+
+    /// Returns true if the file `relative` to `path` passes the include and exclude globs.
+    pub fn accepts(&self, relative: &Path) -> bool {
+        let relative = normalize(relative);
+        let name = relative.rsplit('/').next().unwrap_or_default();
+
+        if !self.hidden && name.starts_with('.') {
+            return false;
+        }
+
+        let matches = |pattern: &String| {
+            if pattern.contains('/') {
+                matches_path(pattern, &relative)
+            } else {
+                glob_match(pattern, name)
+            }
+        };
+
+        (self.include.is_empty() || self.include.iter().any(matches))
+            && !self.exclude.iter().any(matches)
+    }
+}
+
+impl AsRef<Path> for Directory {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Serialized form of a [`Directory`]: a plain path, or a path with options.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum DirectoryEntry {
+    Path(PathBuf),
+    Options(DirectoryOptions),
+}
+
+/// Object form of a [`Directory`]; missing options take their default values.
+#[derive(Serialize, Deserialize)]
+struct DirectoryOptions {
+    path: PathBuf,
+    #[serde(default = "default_true")]
+    recursive: bool,
+    #[serde(default)]
+    max_depth: Option<usize>,
+    #[serde(default)]
+    follow_links: bool,
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+    #[serde(default = "default_true")]
+    hidden: bool,
+    #[serde(default = "default_weight")]
+    weight: u32,
+    #[serde(default)]
+    collection: Option<String>,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl From<DirectoryEntry> for Directory {
+    fn from(entry: DirectoryEntry) -> Self {
+        match entry {
+            DirectoryEntry::Path(path) => Directory::new(path),
+            DirectoryEntry::Options(options) => Directory {
+                path: options.path,
+                recursive: options.recursive,
+                max_depth: options.max_depth,
+                follow_links: options.follow_links,
+                include: options.include,
+                exclude: options.exclude,
+                hidden: options.hidden,
+                weight: options.weight,
+                collection: options.collection,
+            },
+        }
+    }
+}
+
+impl From<Directory> for DirectoryEntry {
+    /// Directories with default options are written back as plain paths.
+    fn from(directory: Directory) -> Self {
+        if directory == Directory::new(&directory.path) {
+            return DirectoryEntry::Path(directory.path);
+        }
+
+        DirectoryEntry::Options(DirectoryOptions {
+            path: directory.path,
+            recursive: directory.recursive,
+            max_depth: directory.max_depth,
+            follow_links: directory.follow_links,
+            include: directory.include,
+            exclude: directory.exclude,
+            hidden: directory.hidden,
+            weight: directory.weight,
+            collection: directory.collection,
+        })
+    }
+}
+
+/// Shuffles `files` so that collections are drawn by weight, then images uniformly
+/// within the drawn collection.
+///
+/// Each file belongs to the directory with the longest matching path. Without any
+/// weight or collection in `directories`, this is a plain shuffle of the whole pool.
+pub fn weighted_shuffle(mut files: Vec<FileInfo>, directories: &[Directory]) -> Vec<FileInfo> {
+    if !directories.iter().any(Directory::is_weighted) {
+        files.shuffle();
+        return files;
+    }
+
+    // Collections in order of appearance: (name, weight, files)
+    let mut collections: Vec<(String, u64, Vec<FileInfo>)> = Vec::new();
+    for directory in directories {
+        let name = directory.collection_name();
+        match collections.iter_mut().find(|(other, ..)| *other == name) {
+            Some((_, weight, _)) => *weight += u64::from(directory.weight),
+            None => collections.push((name, u64::from(directory.weight), Vec::new())),
+        }
+    }
+
+    for file in files {
+        let owner = collection_of(&file.path, directories);
+
+        match collections
+            .iter_mut()
+            .find(|(name, ..)| Some(name) == owner.as_ref())
+        {
+            Some((_, _, members)) => members.push(file),
+            None => collections.push((String::new(), 1, vec![file])),
+        }
+    }
+
+    for (_, _, members) in &mut collections {
+        members.shuffle();
+    }
+
+    let total: usize = collections
+        .iter()
+        .map(|(_, _, members)| members.len())
+        .sum();
+    let mut ordered = Vec::with_capacity(total);
+
+    loop {
+        let weights: Vec<u64> = collections
+            .iter()
+            .map(|(_, weight, members)| if members.is_empty() { 0 } else { *weight })
+            .collect();
+
+        match get_weighted_index(&weights).and_then(|index| collections[index].2.pop()) {
+            Some(file) => ordered.push(file),
+            None => break,
+        }
+    }
+
+    // Collections of weight 0 come last, as one shuffled pool
+    let mut rest: Vec<FileInfo> = collections
+        .into_iter()
+        .flat_map(|(_, _, members)| members)
+        .collect();
+    rest.shuffle();
+    ordered.extend(rest);
+
+    ordered
+}
+
+/// Name of the collection of the directory with the longest path containing `path`.
+///
+/// Returns `None` if no directory contains `path`.
+pub fn collection_of(path: &Path, directories: &[Directory]) -> Option<String> {
+    directories
+        .iter()
+        .filter(|directory| path.starts_with(&directory.path))
+        .max_by_key(|directory| directory.path.components().count())
+        .map(Directory::collection_name)
+}
+
+/// Joins the components of a relative path with '/', whatever the platform.
+fn normalize(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Matches a path glob; a leading "**/" also matches files at the top level.
+fn matches_path(pattern: &str, relative: &str) -> bool {
+    glob_match(pattern, relative)
+        || pattern
+            .strip_prefix("**/")
+            .is_some_and(|rest| glob_match(rest, relative))
+}
+
+/// Matches `text` against a glob: `*` and `?` stop at '/', `**` matches anything.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    glob_match_from(&pattern, &text)
+}
+
+fn glob_match_from(pattern: &[char], text: &[char]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        ['*', '*', '/', rest @ ..] => {
+            // "**/" matches zero or more whole directories
+            glob_match_from(rest, text)
+                || (0..text.len())
+                    .filter(|&i| text[i] == '/')
+                    .any(|i| glob_match_from(rest, &text[i + 1..]))
+        }
+        ['*', '*', rest @ ..] => (0..=text.len()).any(|i| glob_match_from(rest, &text[i..])),
+        ['*', rest @ ..] => (0..=text.len())
+            .take_while(|&i| i == 0 || text[i - 1] != '/')
+            .any(|i| glob_match_from(rest, &text[i..])),
+        ['?', rest @ ..] => {
+            matches!(text.first(), Some(c) if *c != '/') && glob_match_from(rest, &text[1..])
+        }
+        [c, rest @ ..] => text.first() == Some(c) && glob_match_from(rest, &text[1..]),
+    }
+}
+
+//----------------------------------------------------------------------------//
+//                                   Tests                                    //
+//----------------------------------------------------------------------------//
+
+#[cfg(test)]
+mod tests_directory {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*_small.*", "beach_small.jpg"));
+        assert!(!glob_match("*_small.*", "beach.jpg"));
+        assert!(glob_match("img_??.png", "img_01.png"));
+        assert!(!glob_match("*.jpg", "2024/beach.jpg"));
+        assert!(glob_match("**/*.jpg", "2024/06/beach.jpg"));
+        assert!(matches_path("**/thumbnails/**", "thumbnails/beach.jpg"));
+        assert!(matches_path("**/thumbnails/**", "a/b/thumbnails/beach.jpg"));
+        assert!(!matches_path("**/thumbnails/**", "a/thumbnails.jpg"));
+    }
+
+    #[test]
+    fn test_directory_filters() {
+        let nas = Directory {
+            exclude: vec!["**/thumbnails/**".to_string(), "*_small.*".to_string()],
+            hidden: false,
+            ..Directory::new("/mnt/nas")
+        };
+
+        assert!(nas.accepts(Path::new("2024/beach.jpg")));
+        assert!(!nas.accepts(Path::new("2024/beach_small.jpg")));
+        assert!(!nas.accepts(Path::new("2024/thumbnails/beach.jpg")));
+        assert!(!nas.accepts(Path::new(".beach.jpg")));
+        assert!(nas.prunes(Path::new("2024/thumbnails")));
+        assert!(nas.prunes(Path::new(".cache")));
+        assert!(!nas.prunes(Path::new("2024")));
+
+        let landscapes = Directory {
+            include: vec!["landscape*".to_string()],
+            recursive: false,
+            ..Directory::new("/mnt/nas")
+        };
+        assert!(landscapes.accepts(Path::new("landscape_01.png")));
+        assert!(!landscapes.accepts(Path::new("portrait_01.png")));
+        assert_eq!(landscapes.depth_limit(), 1);
+    }
+
+    #[test]
+    fn test_directory_serde() {
+        let json = r#"["/home/user/Pictures", {"path": "/mnt/nas", "max_depth": 2}]"#;
+        let directories: Vec<Directory> = serde_json::from_str(json).unwrap();
+
+        assert_eq!(directories[0], Directory::new("/home/user/Pictures"));
+        assert_eq!(directories[1].max_depth, Some(2));
+        assert!(directories[1].recursive);
+
+        // Plain directories are written back as plain paths
+        let written = serde_json::to_value(&directories).unwrap();
+        assert_eq!(written[0], "/home/user/Pictures");
+        assert_eq!(written[1]["max_depth"], 2);
+    }
+
+    #[test]
+    fn test_weighted_shuffle() {
+        let file = |path: &str| FileInfo {
+            path: PathBuf::from(path),
+            ..FileInfo::default()
+        };
+        let archive: Vec<FileInfo> = (0..500)
+            .map(|i| file(&format!("/archive/{i}.jpg")))
+            .collect();
+        let favourites = vec![file("/archive/best/a.jpg"), file("/archive/best/b.jpg")];
+        let files: Vec<FileInfo> = archive.iter().chain(&favourites).cloned().collect();
+
+        let directories = [
+            Directory::new("/archive"),
+            Directory {
+                weight: 10,
+                ..Directory::new("/archive/best")
+            },
+        ];
+
+        let ordered = crate::with_seed(3, || weighted_shuffle(files.clone(), &directories));
+        assert_eq!(ordered.len(), files.len());
+
+        // The nested favourites folder is drawn far more often than its share of files
+        let is_favourite = |f: &FileInfo| f.path.starts_with("/archive/best");
+        let early = ordered[..10].iter().filter(|f| is_favourite(f)).count();
+        assert_eq!(early, 2, "favourites should lead the pool");
+
+        // A weight of 0 pushes a collection to the end of the pool
+        let muted = [
+            Directory::new("/archive"),
+            Directory {
+                weight: 0,
+                ..Directory::new("/archive/best")
+            },
+        ];
+        let ordered = crate::with_seed(3, || weighted_shuffle(files.clone(), &muted));
+        assert!(!ordered[..500].iter().any(is_favourite));
+    }
+
+    #[test]
+    fn test_weighted_rotation() {
+        use crate::{CanvasBase, CycleRecord, MonitorRecord, ProceduralEffect, State};
+        use std::collections::HashMap;
+
+        let file = |path: String| FileInfo {
+            path: PathBuf::from(path),
+            ..FileInfo::default()
+        };
+        let files: Vec<FileInfo> = (0..50)
+            .map(|i| file(format!("/archive/{i}.jpg")))
+            .chain(["a", "b"].map(|name| file(format!("/favourites/{name}.jpg"))))
+            .collect();
+
+        let directories = [
+            Directory::new("/archive"),
+            Directory {
+                weight: 10,
+                ..Directory::new("/favourites")
+            },
+        ];
+
+        // One image per cycle, drawn like `get_images` does
+        let mut state = State::default();
+        let mut shown: HashMap<PathBuf, usize> = HashMap::new();
+        for cycle in 0..200 {
+            let pool = state.unseen(files.clone(), |path| collection_of(path, &directories));
+            let ordered = crate::with_seed(cycle, || weighted_shuffle(pool, &directories));
+            let path = ordered[0].path.clone();
+            *shown.entry(path.clone()).or_default() += 1;
+
+            state.history.push(CycleRecord::new(vec![MonitorRecord {
+                monitor: 0,
+                output: None,
+                images: vec![path],
+                base: CanvasBase::Photo,
+                effect: ProceduralEffect::None,
+                preset: None,
+            }]));
+        }
+
+        // The favourites start over once both are shown: they keep their 10:1 weight
+        let count = |path: &str| shown.get(Path::new(path)).copied().unwrap_or_default();
+        let favourites = count("/favourites/a.jpg") + count("/favourites/b.jpg");
+        assert!(favourites > 150, "favourites shown {favourites} times");
+
+        // While the archive still rotates: no archive image is shown twice
+        assert!(
+            shown
+                .iter()
+                .filter(|(path, _)| path.starts_with("/archive"))
+                .all(|(_, &times)| times == 1)
+        );
+    }
+}
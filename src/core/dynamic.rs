@@ -0,0 +1,340 @@
+//! Dynamic wallpaper sets: image sequences following the time of day or the sun.
+//!
+//! A manifest lists the frames of a set, keyed by local time (`"HH:MM"`, or
+//! `"sunrise+30"` as in schedules) or by the position of the sun the frame was
+//! shot at, like the solar metadata of macOS `.heic` packs:
+//!
+//! ```json
+//! {
+//!   "frames": [
+//!     { "image": "mojave_01.jpg", "elevation": -12.0, "azimuth": 75.0 },
+//!     { "image": "mojave_02.jpg", "elevation": 15.0, "azimuth": 100.0 },
+//!     { "image": "mojave_16.jpg", "elevation": -35.0, "azimuth": 330.0 }
+//!   ]
+//! }
+//! ```
+//!
+//! Image paths are relative to the manifest. Each cycle shows the frame matching
+//! the current time, cross-faded with the following frame (time keys) or the second
+//! closest one (sun keys) unless `"blend": false`. The `azimuth` tells morning
+//! frames from evening ones; without it, frames are matched by elevation only.
+
+use crate::{LocalTime, Location, SunPosition, TimeOfDay, WallSwitchError, WallSwitchResult};
+use image::RgbImage;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// A sequence of images that changes over the day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DynamicSet {
+    /// Frames of the sequence, in any order.
+    pub frames: Vec<Frame>,
+    /// Cross-fade between neighbouring frames instead of switching abruptly.
+    #[serde(default = "default_true")]
+    pub blend: bool,
+}
+
+/// One image of a dynamic set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Frame {
+    /// Image file, relative to the manifest.
+    pub image: PathBuf,
+    /// When the frame is shown.
+    #[serde(flatten)]
+    pub key: FrameKey,
+}
+
+/// When a frame is shown: at a time of day, or at a position of the sun.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FrameKey {
+    /// Shown from this local time until the time of the next frame.
+    Time { time: TimeOfDay },
+    /// Shown while the sun is closest to this position (degrees).
+    Sun {
+        elevation: f64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        azimuth: Option<f64>,
+    },
+}
+
+/// The frames to show at a given moment.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameBlend {
+    /// Index of the main frame.
+    pub current: usize,
+    /// Index of the frame faded in over `current`.
+    pub next: usize,
+    /// Weight of `next` in the cross-fade, from 0.0 to 1.0.
+    pub progress: f64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl DynamicSet {
+    /// Reads a manifest, resolving the image paths relative to its directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`WallSwitchResult`] if the manifest cannot be read or parsed.
+    pub fn open(path: &Path) -> WallSwitchResult<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut set: DynamicSet = serde_json::from_reader(reader)?;
+
+        let root = path.parent().unwrap_or(Path::new(""));
+        for frame in &mut set.frames {
+            frame.image = root.join(&frame.image);
+        }
+
+        Ok(set)
+    }
+
+    /// Picks the frames to show at `now`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WallSwitchError::EmptySlice`] for a set without frames,
+    /// [`WallSwitchError::MissingValue`] if sun positions are needed without a
+    /// `location`, and [`WallSwitchError::InvalidValue`] if time and sun keys are mixed.
+    pub fn select(
+        &self,
+        now: LocalTime,
+        location: Option<&Location>,
+    ) -> WallSwitchResult<FrameBlend> {
+        if self.frames.is_empty() {
+            return Err(WallSwitchError::EmptySlice);
+        }
+
+        let times: Option<Vec<TimeOfDay>> = self
+            .frames
+            .iter()
+            .map(|frame| match frame.key {
+                FrameKey::Time { time } => Some(time),
+                FrameKey::Sun { .. } => None,
+            })
+            .collect();
+
+        let positions: Option<Vec<(f64, Option<f64>)>> = self
+            .frames
+            .iter()
+            .map(|frame| match frame.key {
+                FrameKey::Sun { elevation, azimuth } => Some((elevation, azimuth)),
+                FrameKey::Time { .. } => None,
+            })
+            .collect();
+
+        let missing_location = || WallSwitchError::MissingValue {
+            arg: "location".to_string(),
+        };
+
+        let blend = match (times, positions) {
+            (Some(times), _) => {
+                select_by_time(&times, now, location).ok_or_else(missing_location)?
+            }
+            (_, Some(positions)) => {
+                let sun = location
+                    .ok_or_else(missing_location)?
+                    .sun_position(now.timestamp);
+                select_by_sun(&positions, sun)
+            }
+            (None, None) => {
+                return Err(WallSwitchError::InvalidValue {
+                    arg: "frames".to_string(),
+                    value: "mixed time and sun keys".to_string(),
+                });
+            }
+        };
+
+        Ok(if self.blend {
+            blend
+        } else {
+            FrameBlend {
+                progress: 0.0,
+                ..blend
+            }
+        })
+    }
+}
+
+/// Shows the last frame whose time has passed, fading into the following one.
+///
+/// Returns `None` if a frame depends on the sun and there is no `location`.
+fn select_by_time(
+    times: &[TimeOfDay],
+    now: LocalTime,
+    location: Option<&Location>,
+) -> Option<FrameBlend> {
+    let daylight = location.map(|location| location.daylight(now.timestamp));
+
+    let mut starts = times
+        .iter()
+        .enumerate()
+        .map(|(index, time)| Some((time.resolve(now, daylight)?, index)))
+        .collect::<Option<Vec<(i64, usize)>>>()?;
+    starts.sort_unstable();
+
+    // Before the first frame of the day, the last frame of the previous day goes on
+    let time = now.seconds_of_day();
+    let position = starts
+        .iter()
+        .rposition(|&(start, _)| start <= time)
+        .unwrap_or(starts.len() - 1);
+
+    let (start, current) = starts[position];
+    let (end, next) = starts[(position + 1) % starts.len()];
+
+    let duration = match (end - start).rem_euclid(SECONDS_PER_DAY) {
+        0 => SECONDS_PER_DAY,
+        seconds => seconds,
+    };
+    let elapsed = (time - start).rem_euclid(SECONDS_PER_DAY);
+
+    Some(FrameBlend {
+        current,
+        next,
+        progress: elapsed as f64 / duration as f64,
+    })
+}
+
+/// Shows the frame closest to the sun, fading towards the second closest one.
+fn select_by_sun(positions: &[(f64, Option<f64>)], sun: SunPosition) -> FrameBlend {
+    let mut distances: Vec<(f64, usize)> = positions
+        .iter()
+        .enumerate()
+        .map(|(index, &(elevation, azimuth))| (sun_distance(sun, elevation, azimuth), index))
+        .collect();
+    distances.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+
+    let (closest, current) = distances[0];
+    let (second, next) = distances.get(1).copied().unwrap_or(distances[0]);
+
+    // Halfway between two frames, both weigh the same
+    let progress = if closest + second > 0.0 {
+        closest / (closest + second)
+    } else {
+        0.0
+    };
+
+    FrameBlend {
+        current,
+        next,
+        progress,
+    }
+}
+
+/// Angle (degrees) between the sun and a frame position; elevation only without azimuth.
+fn sun_distance(sun: SunPosition, elevation: f64, azimuth: Option<f64>) -> f64 {
+    let Some(azimuth) = azimuth else {
+        return (sun.elevation - elevation).abs();
+    };
+
+    let (sun_elevation, elevation) = (sun.elevation.to_radians(), elevation.to_radians());
+    let cos_angle = sun_elevation.sin() * elevation.sin()
+        + sun_elevation.cos() * elevation.cos() * (sun.azimuth - azimuth).to_radians().cos();
+
+    cos_angle.clamp(-1.0, 1.0).acos().to_degrees()
+}
+
+/// Blends `next` over `current` with the weight `progress` (0.0 keeps `current`).
+///
+/// Both images must have the same size.
+pub fn cross_fade(current: &RgbImage, next: &RgbImage, progress: f64) -> RgbImage {
+    let weight = progress.clamp(0.0, 1.0);
+    let mut blended = current.clone();
+
+    for (target, source) in blended.iter_mut().zip(next.iter()) {
+        let mixed = f64::from(*target) + (f64::from(*source) - f64::from(*target)) * weight;
+        *target = mixed.round() as u8;
+    }
+
+    blended
+}
+
+//----------------------------------------------------------------------------//
+//                                   Tests                                    //
+//----------------------------------------------------------------------------//
+
+#[cfg(test)]
+mod tests_dynamic {
+    use super::*;
+    use image::Rgb;
+
+    /// Local time `hours`:`minutes` on 2024-06-21 in UTC+2.
+    fn at(hours: i64, minutes: i64) -> LocalTime {
+        LocalTime {
+            timestamp: 1_718_928_000 + hours * 3600 + minutes * 60 - 7200,
+            utc_offset: 7200,
+        }
+    }
+
+    #[test]
+    fn test_select_by_time() {
+        let set: DynamicSet = serde_json::from_str(
+            r#"{ "frames": [
+                { "image": "night.jpg", "time": "20:00" },
+                { "image": "morning.jpg", "time": "06:00" },
+                { "image": "noon.jpg", "time": "12:00" }
+            ] }"#,
+        )
+        .unwrap();
+
+        // A quarter of the way from 06:00 to 12:00
+        let blend = set.select(at(7, 30), None).unwrap();
+        assert_eq!((blend.current, blend.next), (1, 2));
+        assert!((blend.progress - 0.25).abs() < 1e-9);
+
+        // 02:00 is 6 of the 10 hours of the night frame (20:00 to 06:00)
+        let blend = set.select(at(2, 0), None).unwrap();
+        assert_eq!((blend.current, blend.next), (0, 1));
+        assert!((blend.progress - 0.6).abs() < 1e-9);
+
+        let abrupt = DynamicSet {
+            blend: false,
+            ..set
+        };
+        assert_eq!(abrupt.select(at(2, 0), None).unwrap().progress, 0.0);
+    }
+
+    #[test]
+    fn test_select_by_sun() {
+        let set: DynamicSet = serde_json::from_str(
+            r#"{ "frames": [
+                { "image": "dawn.jpg", "elevation": 0.0, "azimuth": 50.0 },
+                { "image": "noon.jpg", "elevation": 62.0, "azimuth": 180.0 },
+                { "image": "dusk.jpg", "elevation": 0.0, "azimuth": 310.0 },
+                { "image": "night.jpg", "elevation": -30.0 }
+            ] }"#,
+        )
+        .unwrap();
+        let paris = Location {
+            latitude: 48.85,
+            longitude: 2.35,
+        };
+
+        assert_eq!(set.select(at(13, 50), Some(&paris)).unwrap().current, 1);
+        assert_eq!(set.select(at(5, 50), Some(&paris)).unwrap().current, 0);
+        assert_eq!(set.select(at(22, 0), Some(&paris)).unwrap().current, 2);
+        assert_eq!(set.select(at(2, 0), Some(&paris)).unwrap().current, 3);
+
+        // Sun positions need a location
+        assert!(set.select(at(12, 0), None).is_err());
+    }
+
+    #[test]
+    fn test_cross_fade() {
+        let black = RgbImage::from_pixel(4, 4, Rgb([0, 0, 0]));
+        let white = RgbImage::from_pixel(4, 4, Rgb([255, 255, 255]));
+
+        assert_eq!(cross_fade(&black, &white, 0.0), black);
+        assert_eq!(cross_fade(&black, &white, 1.0), white);
+        assert_eq!(cross_fade(&black, &white, 0.5).get_pixel(2, 2).0, [128; 3]);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    Colors, Config, DigitWidth, Dimension, DimensionError, WallSwitchResult,
+    Colors, Config, DigitWidth, Dimension, DimensionError, FocalPoint, WallSwitchResult,
     compute_hashes_parallel, probe_image_dimension,
 };
 use std::{fmt, path::PathBuf};
@@ -52,6 +52,8 @@ pub struct FileInfo {
     pub total: usize,
     /// Dimension: width x height of an image.
     pub dimension: Option<Dimension>,
+    /// Point of interest kept in view when the image is cropped (from the cache).
+    pub focal_point: Option<FocalPoint>,
     /// Evaluated dynamically against the current Config.
     pub is_valid: Option<bool>,
     /// The size of the file, in bytes
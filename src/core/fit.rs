@@ -0,0 +1,221 @@
+//! Fit modes: how a photo of any aspect ratio is placed into its slot on the canvas.
+//!
+//! `fill` crops (see [`CropMode`]) and is the classic behaviour, but it throws away
+//! most of a panorama or of a tall phone photo. The other modes keep the whole
+//! picture visible:
+//!
+//! ```text
+//! fill      crop to the slot ratio, then scale      (no bars, parts cut off)
+//! fit       scale to fit inside, colour bars        (whole photo, bars)
+//! blur      fit over a blurred fill of the photo    (whole photo, no flat bars)
+//! stretch   scale both axes to the slot             (whole photo, distorted)
+//! center    natural size, centred on colour bars    (no scaling)
+//! tile      natural size, repeated from the corner  (no scaling)
+//! ```
+
+use crate::{CropMode, FocalPoint, Resampling};
+use clap::ValueEnum;
+use image::{DynamicImage, Rgb, RgbImage, imageops, imageops::FilterType};
+use serde::{Deserialize, Serialize};
+
+/// The blurred background is computed at 1/`BLUR_DOWNSCALE` of the slot size.
+const BLUR_DOWNSCALE: u32 = 8;
+
+/// Gaussian sigma of the blurred background, in downscaled pixels.
+const BLUR_SIGMA: f32 = 4.0;
+
+/// Brightness of the blurred background (keeps the fitted photo in front).
+const BLUR_BRIGHTNESS: f32 = 0.6;
+
+/// How a photo is placed into its slot on the canvas.
+#[derive(
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum FitMode {
+    /// Crop to the slot aspect ratio and scale (see `crop`).
+    #[value(name = "fill")]
+    #[default]
+    Fill,
+
+    /// Scale to fit inside the slot, with colour bars (`fit_color`).
+    #[value(name = "fit")]
+    Fit,
+
+    /// Scale to fit inside the slot, over a blurred fill of the same photo.
+    #[value(name = "blur")]
+    Blur,
+
+    /// Scale both axes to the slot, ignoring the aspect ratio.
+    #[value(name = "stretch")]
+    Stretch,
+
+    /// Keep the natural size, centred (clipped if larger than the slot).
+    #[value(name = "center")]
+    Center,
+
+    /// Keep the natural size, repeated from the top-left corner.
+    #[value(name = "tile")]
+    Tile,
+}
+
+impl FitMode {
+    /// Returns true if the whole photo stays visible and undistorted in a slot of any shape.
+    pub fn fits_any_aspect(self) -> bool {
+        matches!(self, Self::Fit | Self::Blur | Self::Center)
+    }
+
+    /// Places `img` into a `target` slot and returns a canvas of exactly that size.
+    ///
+    /// `crop` and `focal_point` choose the kept region of `fill` (and of the
+    /// blurred background of `blur`); `color` paints the bars of `fit` and `center`.
+    /// Scaled photos are resampled with `resampling`.
+    pub fn place(
+        self,
+        img: &DynamicImage,
+        target: (u32, u32),
+        crop: CropMode,
+        focal_point: Option<FocalPoint>,
+        color: [u8; 3],
+        resampling: Resampling,
+    ) -> RgbImage {
+        let (w, h) = (target.0.max(1), target.1.max(1));
+
+        match self {
+            Self::Fill => fill(img, (w, h), crop, focal_point, resampling),
+            Self::Fit => {
+                let mut canvas = RgbImage::from_pixel(w, h, Rgb(color));
+                overlay_centred(&mut canvas, &resampling.resize(img, w, h));
+                canvas
+            }
+            Self::Blur => {
+                let small = (w.div_ceil(BLUR_DOWNSCALE), h.div_ceil(BLUR_DOWNSCALE));
+                // The background is blurred anyway: the fast filter is enough
+                let background = fill(img, small, crop, focal_point, Resampling::default());
+                let background = DynamicImage::ImageRgb8(background)
+                    .blur(BLUR_SIGMA)
+                    .resize_exact(w, h, FilterType::Triangle)
+                    .to_rgb8();
+
+                let mut canvas = darken(background, BLUR_BRIGHTNESS);
+                overlay_centred(&mut canvas, &resampling.resize(img, w, h));
+                canvas
+            }
+            Self::Stretch => resampling.resize_exact(img, w, h),
+            Self::Center => {
+                let mut canvas = RgbImage::from_pixel(w, h, Rgb(color));
+                overlay_centred(&mut canvas, &img.to_rgb8());
+                canvas
+            }
+            Self::Tile => {
+                let tile = img.to_rgb8();
+                let mut canvas = RgbImage::new(w, h);
+                for y in (0..h).step_by(tile.height().max(1) as usize) {
+                    for x in (0..w).step_by(tile.width().max(1) as usize) {
+                        imageops::replace(&mut canvas, &tile, i64::from(x), i64::from(y));
+                    }
+                }
+                canvas
+            }
+        }
+    }
+}
+
+/// Crops `img` to the aspect ratio of `target` and scales it to exactly that size.
+fn fill(
+    img: &DynamicImage,
+    target: (u32, u32),
+    crop: CropMode,
+    focal_point: Option<FocalPoint>,
+    resampling: Resampling,
+) -> RgbImage {
+    let window = crop.window(img, target, focal_point);
+    let cropped = img.crop_imm(window.x, window.y, window.width, window.height);
+    resampling.resize_exact(&cropped, target.0, target.1)
+}
+
+/// Draws `top` centred on `canvas`; anything outside the canvas is clipped.
+fn overlay_centred(canvas: &mut RgbImage, top: &RgbImage) {
+    let x = (i64::from(canvas.width()) - i64::from(top.width())) / 2;
+    let y = (i64::from(canvas.height()) - i64::from(top.height())) / 2;
+    imageops::replace(canvas, top, x, y);
+}
+
+/// Scales every channel of `img` by `factor`.
+fn darken(mut img: RgbImage, factor: f32) -> RgbImage {
+    for channel in img.iter_mut() {
+        *channel = (f32::from(*channel) * factor) as u8;
+    }
+    img
+}
+
+//----------------------------------------------------------------------------//
+//                                   Tests                                    //
+//----------------------------------------------------------------------------//
+
+#[cfg(test)]
+mod tests_fit {
+    use super::*;
+
+    /// Tall "phone photo": white with a red top row and a blue bottom row.
+    fn phone_photo() -> DynamicImage {
+        let mut photo = RgbImage::from_pixel(90, 160, Rgb([255, 255, 255]));
+        for x in 0..90 {
+            photo.put_pixel(x, 0, Rgb([255, 0, 0]));
+            photo.put_pixel(x, 159, Rgb([0, 0, 255]));
+        }
+        DynamicImage::ImageRgb8(photo)
+    }
+
+    #[test]
+    fn test_fit_modes_fill_the_slot() {
+        let photo = phone_photo();
+        let bars = [10, 20, 30];
+        let resampling = Resampling::default();
+
+        for mode in FitMode::value_variants() {
+            let canvas = mode.place(&photo, (320, 180), CropMode::Center, None, bars, resampling);
+            assert_eq!(canvas.dimensions(), (320, 180), "{mode:?}");
+        }
+
+        // Fit keeps the whole photo: bars on the sides, both coloured rows visible
+        let fitted =
+            FitMode::Fit.place(&photo, (320, 180), CropMode::Center, None, bars, resampling);
+        assert_eq!(fitted.get_pixel(0, 90).0, bars);
+        assert_eq!(fitted.get_pixel(319, 90).0, bars);
+        assert!(fitted.get_pixel(160, 0).0[0] > 200);
+        assert!(fitted.get_pixel(160, 179).0[2] > 200);
+
+        // Fill crops the coloured rows away
+        let filled =
+            FitMode::Fill.place(&photo, (320, 180), CropMode::Center, None, bars, resampling);
+        assert_eq!(filled.get_pixel(160, 0).0, [255, 255, 255]);
+
+        // Blur replaces the flat bars with the (darkened) photo itself
+        let blurred =
+            FitMode::Blur.place(&photo, (320, 180), CropMode::Center, None, bars, resampling);
+        assert_ne!(blurred.get_pixel(0, 90).0, bars);
+
+        // Center keeps the natural size: 90x160 centred on the bars
+        let centred =
+            FitMode::Center.place(&photo, (320, 180), CropMode::Center, None, bars, resampling);
+        assert_eq!(centred.get_pixel(114, 90).0, bars);
+        assert_eq!(centred.get_pixel(115, 10).0, [255, 0, 0]);
+
+        // Tile repeats the photo every 90 pixels
+        let tiled =
+            FitMode::Tile.place(&photo, (320, 180), CropMode::Center, None, bars, resampling);
+        assert_eq!(tiled.get_pixel(180, 160).0, [255, 0, 0]);
+    }
+}
@@ -0,0 +1,229 @@
+//! Framing of multi-picture canvases: gutters, outer margin, rounded corners and shadows.
+//!
+//! The layout is computed on the canvas minus the outer `margin`, then every cell
+//! is shrunk by the `gutter` so that neighbouring pictures are separated by
+//! exactly `gutter` pixels of `gutter_color`:
+//!
+//! ```text
+//! +--------------------------------+
+//! |   margin                       |
+//! |   +--------+ gutter +------+   |
+//! |   | cell 0 |        |cell 1|   |
+//! |   +--------+        +------+   |
+//! +--------------------------------+
+//! ```
+//!
+//! Rounded corners and drop shadows use the signed distance to a rounded rectangle,
+//! so their edges are anti-aliased.
+
+use crate::{Cell, Dimension, Monitor};
+use image::RgbImage;
+
+/// Offset of the drop shadow (right and down), in pixels.
+const SHADOW_OFFSET: f64 = 6.0;
+
+/// Width of the soft edge of the drop shadow, in pixels.
+const SHADOW_SOFTNESS: f64 = 14.0;
+
+/// Darkening at the core of the drop shadow (0.0 to 1.0).
+const SHADOW_OPACITY: f64 = 0.55;
+
+impl Monitor {
+    /// Divides the canvas into one framed cell per image (see [`crate::Layout::cells`]).
+    ///
+    /// The layout is computed inside the outer `margin`, and each cell is shrunk
+    /// to leave a `gutter` between neighbours.
+    pub fn framed_cells(&self, aspects: &[f64]) -> Vec<Cell> {
+        let (margin, gutter) = (u64::from(self.margin), u64::from(self.gutter));
+
+        // Laying out an area one gutter larger and shrinking every cell by one gutter
+        // leaves exactly one gutter between neighbours and none at the border.
+        let area = Monitor {
+            resolution: Dimension {
+                width: (self.resolution.width + gutter)
+                    .saturating_sub(2 * margin)
+                    .max(1),
+                height: (self.resolution.height + gutter)
+                    .saturating_sub(2 * margin)
+                    .max(1),
+            },
+            ..self.clone()
+        };
+
+        self.layout
+            .cells(&area, aspects)
+            .into_iter()
+            .map(|cell| Cell {
+                x: cell.x + self.margin,
+                y: cell.y + self.margin,
+                width: cell.width.saturating_sub(self.gutter).max(1),
+                height: cell.height.saturating_sub(self.gutter).max(1),
+            })
+            .collect()
+    }
+}
+
+/// Darkens the canvas under the soft drop shadow of a `cell`.
+pub fn draw_shadow(canvas: &mut RgbImage, cell: Cell, radius: u32) {
+    let shadow = RoundedRect::new(cell, radius, SHADOW_OFFSET);
+    let reach = (SHADOW_OFFSET + SHADOW_SOFTNESS).ceil() as u32;
+
+    let x_end = (cell.x + cell.width + reach).min(canvas.width());
+    let y_end = (cell.y + cell.height + reach).min(canvas.height());
+
+    for y in cell.y.saturating_sub(reach)..y_end {
+        for x in cell.x.saturating_sub(reach)..x_end {
+            // Soft edge: full shadow half a softness inside, none half a softness outside
+            let coverage = (0.5 - shadow.distance(x, y) / SHADOW_SOFTNESS).clamp(0.0, 1.0);
+            if coverage > 0.0 {
+                let factor = 1.0 - SHADOW_OPACITY * coverage;
+                for channel in &mut canvas.get_pixel_mut(x, y).0 {
+                    *channel = (f64::from(*channel) * factor) as u8;
+                }
+            }
+        }
+    }
+}
+
+/// Draws `picture` (sized to the `cell`) onto the canvas with rounded corners.
+///
+/// Pixels cut off by the corners keep the canvas underneath (gutter or shadow).
+pub fn draw_picture(canvas: &mut RgbImage, picture: &RgbImage, cell: Cell, radius: u32) {
+    if radius == 0 {
+        image::imageops::replace(canvas, picture, i64::from(cell.x), i64::from(cell.y));
+        return;
+    }
+
+    let shape = RoundedRect::new(cell, radius, 0.0);
+    let radius = radius.min(cell.width / 2).min(cell.height / 2);
+
+    for (px, py, pixel) in picture.enumerate_pixels() {
+        let (x, y) = (cell.x + px, cell.y + py);
+        if x >= canvas.width() || y >= canvas.height() {
+            continue;
+        }
+
+        // Only the corner squares need the distance field
+        let in_corner = (px < radius || px >= cell.width - radius)
+            && (py < radius || py >= cell.height - radius);
+        let coverage = if in_corner {
+            (0.5 - shape.distance(x, y)).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+
+        let under = canvas.get_pixel_mut(x, y);
+        for (dst, src) in under.0.iter_mut().zip(pixel.0) {
+            *dst = (f64::from(src) * coverage + f64::from(*dst) * (1.0 - coverage)).round() as u8;
+        }
+    }
+}
+
+/// Rounded rectangle in canvas coordinates, for signed distance queries.
+struct RoundedRect {
+    centre: (f64, f64),
+    half: (f64, f64),
+    radius: f64,
+}
+
+impl RoundedRect {
+    /// Rectangle of a `cell` moved by `offset` pixels right and down.
+    fn new(cell: Cell, radius: u32, offset: f64) -> Self {
+        let half = (f64::from(cell.width) / 2.0, f64::from(cell.height) / 2.0);
+        RoundedRect {
+            centre: (
+                f64::from(cell.x) + half.0 + offset,
+                f64::from(cell.y) + half.1 + offset,
+            ),
+            half,
+            radius: f64::from(radius).min(half.0).min(half.1),
+        }
+    }
+
+    /// Signed distance from the centre of pixel (x, y) to the edge (negative inside).
+    fn distance(&self, x: u32, y: u32) -> f64 {
+        let qx = (f64::from(x) + 0.5 - self.centre.0).abs() - (self.half.0 - self.radius);
+        let qy = (f64::from(y) + 0.5 - self.centre.1).abs() - (self.half.1 - self.radius);
+
+        let outside = qx.max(0.0).hypot(qy.max(0.0));
+        let inside = qx.max(qy).min(0.0);
+        outside + inside - self.radius
+    }
+}
+
+//----------------------------------------------------------------------------//
+//                                   Tests                                    //
+//----------------------------------------------------------------------------//
+
+#[cfg(test)]
+mod tests_frame {
+    use super::*;
+    use crate::Layout;
+    use image::Rgb;
+
+    #[test]
+    fn test_framed_cells() {
+        let monitor = Monitor {
+            pictures_per_monitor: 4,
+            resolution: Dimension {
+                width: 1000,
+                height: 600,
+            },
+            layout: Layout::Grid,
+            gutter: 20,
+            margin: 40,
+            ..Monitor::default()
+        };
+
+        let cells = monitor.framed_cells(&[1.0; 4]);
+        assert_eq!(
+            cells[0],
+            Cell {
+                x: 40,
+                y: 40,
+                width: 450,
+                height: 250
+            }
+        );
+        // One gutter between neighbours, one margin at the border
+        assert_eq!(cells[1].x, 40 + 450 + 20);
+        assert_eq!(cells[3].x + cells[3].width, 1000 - 40);
+        assert_eq!(cells[3].y + cells[3].height, 600 - 40);
+
+        // No framing: the plain layout
+        let plain = Monitor {
+            gutter: 0,
+            margin: 0,
+            ..monitor
+        };
+        assert_eq!(
+            plain.framed_cells(&[1.0; 4]),
+            Layout::Grid.cells(&plain, &[1.0; 4])
+        );
+    }
+
+    #[test]
+    fn test_rounded_corners_and_shadow() {
+        let background = Rgb([200, 200, 200]);
+        let cell = Cell {
+            x: 20,
+            y: 20,
+            width: 60,
+            height: 40,
+        };
+        let picture = RgbImage::from_pixel(60, 40, Rgb([255, 0, 0]));
+
+        let mut canvas = RgbImage::from_pixel(100, 100, background);
+        draw_shadow(&mut canvas, cell, 10);
+        draw_picture(&mut canvas, &picture, cell, 10);
+
+        // Corners keep the canvas, the middle and edges show the picture
+        assert_ne!(canvas.get_pixel(20, 20).0, [255, 0, 0]);
+        assert_eq!(canvas.get_pixel(50, 20).0, [255, 0, 0]);
+        assert_eq!(canvas.get_pixel(50, 40).0, [255, 0, 0]);
+
+        // The shadow darkens below and right of the picture, not above
+        assert!(canvas.get_pixel(50, 63).0[0] < 150);
+        assert_eq!(canvas.get_pixel(50, 5).0, background.0);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{CanvasBase, FileInfo, ProceduralEffect};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// What a single monitor displayed during one wallpaper cycle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorRecord {
+    /// Index of the monitor entry in `Config::monitors`.
+    pub monitor: usize,
+    /// Output the monitor entry was bound to, if any.
+    #[serde(default)]
+    pub output: Option<String>,
+    /// Source images composed onto the monitor canvas.
+    pub images: Vec<PathBuf>,
+    /// Canvas the overlay was drawn on (photos, or a generated base).
+    #[serde(default)]
+    pub base: CanvasBase,
+    /// Concrete overlay effect that was rendered (never a meta-variant).
+    #[serde(default)]
+    pub effect: ProceduralEffect,
+    /// Name of the effect preset (e.g. a Julia constant) picked by the generator.
+    #[serde(default)]
+    pub preset: Option<String>,
+}
+
+/// One applied wallpaper set, grouped per cycle rather than per path.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CycleRecord {
+    /// Unix timestamp (seconds) of the moment the set was applied.
+    pub timestamp: u64,
+    /// Tool that displayed the set (e.g. "awww", "gsettings", "feh").
+    #[serde(default)]
+    pub backend: String,
+    /// Seed of the random generator used for the cycle, `None` when unseeded.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Per-monitor details, in monitor order.
+    pub monitors: Vec<MonitorRecord>,
+}
+
+impl CycleRecord {
+    /// Creates a record stamped with the current time.
+    pub fn new(monitors: Vec<MonitorRecord>) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        CycleRecord {
+            timestamp,
+            backend: String::new(),
+            seed: None,
+            monitors,
+        }
+    }
+
+    /// Returns the source images of every monitor, flattened in monitor order.
+    ///
+    /// Only the path is filled in, which is all the canvas compiler needs to
+    /// re-apply the set.
+    pub fn images(&self) -> Vec<FileInfo> {
+        self.monitors
+            .iter()
+            .flat_map(|monitor| &monitor.images)
+            .map(|path| FileInfo {
+                path: path.clone(),
+                ..FileInfo::default()
+            })
+            .collect()
+    }
+
+    /// Returns the rendered effect of every monitor, in monitor order.
+    pub fn effects(&self) -> Vec<ProceduralEffect> {
+        self.monitors.iter().map(|monitor| monitor.effect).collect()
+    }
+
+    /// Formats the timestamp as `YYYY-MM-DD HH:MM:SS UTC`.
+    pub fn date_time(&self) -> String {
+        let days = (self.timestamp / 86_400) as i64;
+        let secs = self.timestamp % 86_400;
+        let (year, month, day) = civil_from_days(days);
+
+        format!(
+            "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+            secs / 3600,
+            (secs % 3600) / 60,
+            secs % 60
+        )
+    }
+}
+
+/// Converts days since 1970-01-01 into a proleptic Gregorian (year, month, day).
+///
+/// Algorithm from Howard Hinnant's `chrono`-compatible date library.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+//----------------------------------------------------------------------------//
+//                                   Tests                                    //
+//----------------------------------------------------------------------------//
+
+#[cfg(test)]
+mod tests_history {
+    use super::*;
+
+    #[test]
+    fn test_date_time() {
+        let mut record = CycleRecord::new(Vec::new());
+
+        record.timestamp = 0;
+        assert_eq!(record.date_time(), "1970-01-01 00:00:00 UTC");
+
+        record.timestamp = 1_700_000_000;
+        assert_eq!(record.date_time(), "2023-11-14 22:13:20 UTC");
+
+        record.timestamp = 951_782_400; // leap day
+        assert_eq!(record.date_time(), "2000-02-29 00:00:00 UTC");
+    }
+
+    #[test]
+    fn test_images_and_effects() {
+        let record = CycleRecord::new(vec![
+            MonitorRecord {
+                monitor: 0,
+                output: Some("DP-1".to_string()),
+                images: vec![PathBuf::from("/a.png"), PathBuf::from("/b.png")],
+                base: CanvasBase::Photo,
+                effect: ProceduralEffect::JuliaSet,
+                preset: Some("Coral reefs".to_string()),
+            },
+            MonitorRecord {
+                monitor: 1,
+                output: None,
+                images: vec![PathBuf::from("/c.png")],
+                base: CanvasBase::Photo,
+                effect: ProceduralEffect::None,
+                preset: None,
+            },
+        ]);
+
+        let paths: Vec<PathBuf> = record.images().into_iter().map(|f| f.path).collect();
+        assert_eq!(
+            paths,
+            ["/a.png", "/b.png", "/c.png"].map(PathBuf::from).to_vec()
+        );
+        assert_eq!(
+            record.effects(),
+            vec![ProceduralEffect::JuliaSet, ProceduralEffect::None]
+        );
+    }
+}
@@ -0,0 +1,445 @@
+//! Picture layouts: how a monitor canvas is divided into one cell per image.
+//!
+//! ```text
+//! strips      N rows (Horizontal) or N columns (Vertical) of equal size
+//! grid        rows x columns of equal cells (2x2, 3x2, ...), auto-sized by default
+//! hero        one large cell (2/3 of the canvas) plus a strip of thumbnails
+//! justified   rows (or columns) packed by the aspect ratio of each image
+//! ```
+//!
+//! Cells tile the whole canvas without gaps; remainder pixels go to the first cells.
+
+use crate::{Dimension, Monitor, Orientation};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// Share of the canvas (numerator / denominator) taken by the hero cell.
+const HERO_SHARE: (u32, u32) = (2, 3);
+
+/// How the pictures of a monitor are arranged on its canvas.
+#[derive(
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Layout {
+    /// Equal strips stacked along the `picture_orientation`.
+    #[value(name = "strips")]
+    #[default]
+    Strips,
+
+    /// Equal cells in rows and columns (`columns`, or as square as possible).
+    #[value(name = "grid")]
+    Grid,
+
+    /// The first picture large, the others as thumbnails beside it.
+    #[value(name = "hero")]
+    Hero,
+
+    /// Rows (Horizontal) or columns (Vertical) packed by image aspect ratio.
+    #[value(name = "justified")]
+    Justified,
+}
+
+/// Region of the monitor canvas given to one picture, in canvas pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Cell {
+    /// Swaps the horizontal and vertical axes.
+    fn transpose(self) -> Self {
+        Cell {
+            x: self.y,
+            y: self.x,
+            width: self.height,
+            height: self.width,
+        }
+    }
+}
+
+impl Layout {
+    /// Returns true if the cells depend on the aspect ratio of the images.
+    pub fn is_adaptive(self) -> bool {
+        self == Self::Justified
+    }
+
+    /// Divides the canvas of `monitor` into one cell per image, in image order.
+    ///
+    /// `aspects` holds the width / height ratio of each image; only its length
+    /// matters to the fixed layouts.
+    pub fn cells(self, monitor: &Monitor, aspects: &[f64]) -> Vec<Cell> {
+        let count = aspects.len();
+        let width = monitor.resolution.width as u32;
+        let height = monitor.resolution.height as u32;
+        let horizontal = monitor.picture_orientation == Orientation::Horizontal;
+
+        if count == 0 {
+            return Vec::new();
+        }
+
+        // Vertical variants are the horizontal ones computed on the transposed canvas
+        let (cells, transposed) = match self {
+            Self::Strips if horizontal => (strips(width, height, count), false),
+            Self::Strips => (strips(height, width, count), true),
+            Self::Grid => (grid(width, height, count, monitor.columns), false),
+            Self::Hero if horizontal => (hero(width, height, count), false),
+            Self::Hero => (hero(height, width, count), true),
+            Self::Justified if horizontal => (justified(width, height, aspects), false),
+            Self::Justified => {
+                let inverted: Vec<f64> = aspects.iter().map(|aspect| 1.0 / aspect).collect();
+                (justified(height, width, &inverted), true)
+            }
+        };
+
+        if transposed {
+            cells.into_iter().map(Cell::transpose).collect()
+        } else {
+            cells
+        }
+    }
+}
+
+impl Monitor {
+    /// Size of each picture cell, for the aspect-ratio matching of candidate images.
+    ///
+    /// Adaptive layouts, and fit modes that crop nothing, fit any image and yield `None` cells.
+    pub fn cell_sizes(&self) -> Vec<Option<Dimension>> {
+        let count = self.pictures_needed();
+
+        if self.layout.is_adaptive() || self.fit.fits_any_aspect() {
+            return vec![None; count];
+        }
+
+        self.framed_cells(&vec![1.0; count])
+            .into_iter()
+            .map(|cell| {
+                Some(Dimension {
+                    width: cell.width.into(),
+                    height: cell.height.into(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// `count` rows of equal height spanning the full `width`.
+fn strips(width: u32, height: u32, count: usize) -> Vec<Cell> {
+    split(height, &vec![1.0; count])
+        .into_iter()
+        .map(|(y, h)| Cell {
+            x: 0,
+            y,
+            width,
+            height: h,
+        })
+        .collect()
+}
+
+/// Rows of `columns` equal cells; the last row shares its width among what is left.
+fn grid(width: u32, height: u32, count: usize, columns: Option<u8>) -> Vec<Cell> {
+    let columns = match columns {
+        Some(columns) => usize::from(columns).clamp(1, count),
+        None => {
+            // As square as possible, with the longer side along the longer canvas side
+            let short = count.isqrt();
+            let long = count.div_ceil(short);
+            if width >= height { long } else { short }
+        }
+    };
+    let rows = count.div_ceil(columns);
+
+    split(height, &vec![1.0; rows])
+        .into_iter()
+        .enumerate()
+        .flat_map(|(row, (y, h))| {
+            let in_row = columns.min(count - row * columns);
+            split(width, &vec![1.0; in_row])
+                .into_iter()
+                .map(move |(x, w)| Cell {
+                    x,
+                    y,
+                    width: w,
+                    height: h,
+                })
+        })
+        .collect()
+}
+
+/// A hero cell on top, the other pictures in a row of thumbnails below it.
+fn hero(width: u32, height: u32, count: usize) -> Vec<Cell> {
+    if count == 1 {
+        return strips(width, height, 1);
+    }
+
+    let hero_h = height * HERO_SHARE.0 / HERO_SHARE.1;
+    let hero = Cell {
+        x: 0,
+        y: 0,
+        width,
+        height: hero_h,
+    };
+
+    let thumbnails = split(width, &vec![1.0; count - 1])
+        .into_iter()
+        .map(|(x, w)| Cell {
+            x,
+            y: hero_h,
+            width: w,
+            height: height - hero_h,
+        });
+
+    std::iter::once(hero).chain(thumbnails).collect()
+}
+
+/// Rows of consecutive images whose heights let every image keep its aspect ratio.
+///
+/// The number of rows makes the images cover the canvas at a common height:
+/// `rows = sqrt(sum(aspects) * height / width)`. Row heights are then scaled to
+/// fill the canvas exactly, which costs only a small crop.
+fn justified(width: u32, height: u32, aspects: &[f64]) -> Vec<Cell> {
+    let aspects: Vec<f64> = aspects
+        .iter()
+        .map(|&aspect| {
+            if aspect.is_finite() && aspect > 0.0 {
+                aspect
+            } else {
+                1.0
+            }
+        })
+        .collect();
+    let total: f64 = aspects.iter().sum();
+
+    let rows = (total * f64::from(height) / f64::from(width.max(1)))
+        .sqrt()
+        .round()
+        .clamp(1.0, aspects.len() as f64) as usize;
+
+    // Assign each image to the row holding the middle of its share of the total width
+    let mut row_of = Vec::with_capacity(aspects.len());
+    let mut before = 0.0;
+    for &aspect in &aspects {
+        let row = ((before + aspect / 2.0) / total * rows as f64) as usize;
+        row_of.push(row.min(rows - 1));
+        before += aspect;
+    }
+
+    let mut row_aspects: Vec<Vec<f64>> = vec![Vec::new(); rows];
+    for (&row, &aspect) in row_of.iter().zip(&aspects) {
+        row_aspects[row].push(aspect);
+    }
+    row_aspects.retain(|row| !row.is_empty());
+
+    // A row of wide images is low: its height weight is the inverse of its total aspect
+    let heights: Vec<f64> = row_aspects
+        .iter()
+        .map(|row| 1.0 / row.iter().sum::<f64>())
+        .collect();
+
+    split(height, &heights)
+        .into_iter()
+        .zip(&row_aspects)
+        .flat_map(|((y, h), row)| {
+            split(width, row).into_iter().map(move |(x, w)| Cell {
+                x,
+                y,
+                width: w,
+                height: h,
+            })
+        })
+        .collect()
+}
+
+/// Splits `total` pixels into consecutive `(offset, length)` parts proportional to `weights`.
+///
+/// Equal weights give the remainder pixels to the first parts, one each.
+fn split(total: u32, weights: &[f64]) -> Vec<(u32, u32)> {
+    let count = weights.len() as u32;
+    if count == 0 {
+        return Vec::new();
+    }
+
+    if weights.windows(2).all(|pair| pair[0] == pair[1]) {
+        let (base, remainder) = (total / count, total % count);
+        let mut offset = 0;
+        return (0..count)
+            .map(|index| {
+                let length = base + u32::from(index < remainder);
+                let part = (offset, length);
+                offset += length;
+                part
+            })
+            .collect();
+    }
+
+    let sum: f64 = weights.iter().sum();
+    let mut cumulative = 0.0;
+    let mut offset = 0;
+    weights
+        .iter()
+        .map(|weight| {
+            cumulative += weight;
+            let end = ((cumulative / sum) * f64::from(total)).round() as u32;
+            let end = end.clamp(offset, total);
+            let part = (offset, end - offset);
+            offset = end;
+            part
+        })
+        .collect()
+}
+
+//----------------------------------------------------------------------------//
+//                                   Tests                                    //
+//----------------------------------------------------------------------------//
+
+#[cfg(test)]
+mod tests_layout {
+    use super::*;
+
+    fn monitor(layout: Layout, orientation: Orientation) -> Monitor {
+        Monitor {
+            picture_orientation: orientation,
+            resolution: Dimension {
+                width: 1920,
+                height: 1080,
+            },
+            layout,
+            ..Monitor::default()
+        }
+    }
+
+    /// Cells must tile the canvas exactly: no overlap, no gap.
+    fn assert_tiles(cells: &[Cell], width: u32, height: u32) {
+        let area: u64 = cells
+            .iter()
+            .map(|c| u64::from(c.width) * u64::from(c.height))
+            .sum();
+        assert_eq!(area, u64::from(width) * u64::from(height), "{cells:?}");
+        for c in cells {
+            assert!(c.x + c.width <= width && c.y + c.height <= height, "{c:?}");
+        }
+    }
+
+    #[test]
+    fn test_fixed_layouts() {
+        // Strips keep the historical split: remainder pixels to the first strips
+        let strips =
+            Layout::Strips.cells(&monitor(Layout::Strips, Orientation::Horizontal), &[1.0; 7]);
+        assert_eq!((strips[0].height, strips[6].height), (155, 154));
+        assert_tiles(&strips, 1920, 1080);
+
+        let columns =
+            Layout::Strips.cells(&monitor(Layout::Strips, Orientation::Vertical), &[1.0; 3]);
+        assert_eq!(
+            columns[2],
+            Cell {
+                x: 1280,
+                y: 0,
+                width: 640,
+                height: 1080
+            }
+        );
+
+        // Auto grid: 4 -> 2x2, 6 -> 3x2, 5 -> 3 + 2
+        let grid = monitor(Layout::Grid, Orientation::Horizontal);
+        assert_eq!(
+            Layout::Grid.cells(&grid, &[1.0; 4])[3],
+            Cell {
+                x: 960,
+                y: 540,
+                width: 960,
+                height: 540
+            }
+        );
+        assert_eq!(Layout::Grid.cells(&grid, &[1.0; 6])[2].width, 640);
+        let five = Layout::Grid.cells(&grid, &[1.0; 5]);
+        assert_eq!((five[2].width, five[4].width), (640, 960));
+        assert_tiles(&five, 1920, 1080);
+
+        // Fixed number of columns
+        let fixed = Monitor {
+            columns: Some(4),
+            ..grid
+        };
+        assert_eq!(Layout::Grid.cells(&fixed, &[1.0; 8])[7].x, 1440);
+
+        let hero = Layout::Hero.cells(&monitor(Layout::Hero, Orientation::Horizontal), &[1.0; 4]);
+        assert_eq!(
+            hero[0],
+            Cell {
+                x: 0,
+                y: 0,
+                width: 1920,
+                height: 720
+            }
+        );
+        assert_eq!(
+            hero[3],
+            Cell {
+                x: 1280,
+                y: 720,
+                width: 640,
+                height: 360
+            }
+        );
+        assert_tiles(&hero, 1920, 1080);
+    }
+
+    #[test]
+    fn test_justified_layout() {
+        let justified = monitor(Layout::Justified, Orientation::Horizontal);
+
+        // Panoramas and 3:2 photos, laid out row by row in image order
+        let aspects = [4.0, 4.0, 1.5, 1.5, 1.5, 1.5];
+        let cells = Layout::Justified.cells(&justified, &aspects);
+        assert_eq!(cells.len(), 6);
+        assert_tiles(&cells, 1920, 1080);
+        assert_eq!(
+            cells[0],
+            Cell {
+                x: 0,
+                y: 0,
+                width: 1920,
+                height: 413
+            }
+        );
+        assert_eq!(cells[5].y + cells[5].height, 1080);
+
+        // Each cell stays close to the aspect ratio of its image
+        for (cell, aspect) in cells.iter().zip(aspects) {
+            let ratio = f64::from(cell.width) / f64::from(cell.height);
+            assert!((ratio / aspect - 1.0).abs() < 0.35, "{cell:?} for {aspect}");
+        }
+
+        // Vertical: columns of consecutive images
+        let vertical = Monitor {
+            picture_orientation: Orientation::Vertical,
+            ..justified
+        };
+        let columns = Layout::Justified.cells(&vertical, &[0.5; 4]);
+        assert_tiles(&columns, 1920, 1080);
+        assert_eq!(
+            columns[1],
+            Cell {
+                x: 480,
+                y: 0,
+                width: 480,
+                height: 1080
+            }
+        );
+    }
+}
@@ -0,0 +1,242 @@
+//! Aspect-ratio-aware assignment of candidate images to monitor slots.
+//!
+//! Every slot (one picture of one monitor) is paired with the image that loses
+//! the least area when cropped to the slot's aspect ratio:
+//!
+//! ```text
+//! crop loss = 1 - min(r_image / r_slot, r_slot / r_image)     r = width / height
+//! ```
+//!
+//! Pairs are taken greedily from the lowest loss upwards; ties keep the shuffled
+//! order of the pool, so equally fitting images still rotate randomly.
+
+use crate::{Config, Dimension, FileInfo, Monitor, Orientation, physical_bounds, physical_layout};
+
+/// Picks the images of one cycle from a `pool` of valid images, in slot order.
+///
+/// With aspect matching (see [`Config::matches_aspect`]) each slot of `monitors`, or the
+/// spanned desktop, gets its best fit; otherwise the first `needed` images are kept.
+pub fn select_cycle_images(
+    mut pool: Vec<FileInfo>,
+    needed: usize,
+    monitors: &[Monitor],
+    config: &Config,
+) -> Vec<FileInfo> {
+    if !config.matches_aspect() {
+        pool.truncate(needed);
+        return pool;
+    }
+
+    if config.span {
+        match_spanned(pool, monitors, &config.monitor_orientation, config.max_crop)
+    } else {
+        match_images(pool, monitors, config.max_crop)
+    }
+}
+
+/// Fraction (0.0 to 1.0) of `image` cropped away when filling a `slot`.
+///
+/// Unknown or empty dimensions count as a full loss.
+pub fn crop_loss(image: &Dimension, slot: &Dimension) -> f64 {
+    if image.width == 0 || image.height == 0 || slot.width == 0 || slot.height == 0 {
+        return 1.0;
+    }
+
+    let image_ratio = image.width as f64 / image.height as f64;
+    let slot_ratio = slot.width as f64 / slot.height as f64;
+
+    1.0 - (image_ratio / slot_ratio).min(slot_ratio / image_ratio)
+}
+
+/// Picks, from a `pool` of valid images, the set that best fits the slots of `monitors`.
+///
+/// The result is ordered slot by slot (the order consumed by the monitor partitions)
+/// and has one image per slot, or fewer if the pool is too small. Images losing more
+/// than `max_crop` percent are skipped for a slot while a better fitting one remains;
+/// slots left empty that way take the best of the rest.
+pub fn match_images(
+    pool: Vec<FileInfo>,
+    monitors: &[Monitor],
+    max_crop: Option<u8>,
+) -> Vec<FileInfo> {
+    let slots: Vec<Option<Dimension>> = monitors.iter().flat_map(Monitor::cell_sizes).collect();
+    match_slots(pool, &slots, max_crop)
+}
+
+/// Picks, from a `pool` of valid images, the one that best spans the whole desktop.
+///
+/// The single slot is the bounding box of the physical layout of `monitors`
+/// (bezels included), so panoramas win on a row of screens.
+pub fn match_spanned(
+    pool: Vec<FileInfo>,
+    monitors: &[Monitor],
+    orientation: &Orientation,
+    max_crop: Option<u8>,
+) -> Vec<FileInfo> {
+    let desktop = physical_bounds(&physical_layout(monitors, orientation));
+
+    // Tenths of a millimetre: only the aspect ratio matters
+    let slot = Dimension {
+        width: (desktop.width * 10.0) as u64,
+        height: (desktop.height * 10.0) as u64,
+    };
+    match_slots(pool, &[Some(slot)], max_crop)
+}
+
+/// Assigns one image of the `pool` to each slot (`None` slots fit any image).
+fn match_slots(
+    pool: Vec<FileInfo>,
+    slots: &[Option<Dimension>],
+    max_crop: Option<u8>,
+) -> Vec<FileInfo> {
+    let tolerance = max_crop.map_or(1.0, |percent| f64::from(percent.min(100)) / 100.0);
+
+    let mut pairs: Vec<(f64, usize, usize)> = slots
+        .iter()
+        .enumerate()
+        .flat_map(|(slot_index, slot)| {
+            pool.iter().enumerate().map(move |(image_index, image)| {
+                // Cells of adaptive layouts fit any image
+                let loss = match (&image.dimension, slot) {
+                    (_, None) => 0.0,
+                    (Some(dimension), Some(slot)) => crop_loss(dimension, slot),
+                    (None, Some(_)) => 1.0,
+                };
+                (loss, slot_index, image_index)
+            })
+        })
+        .collect();
+
+    // Stable sort: equal losses keep the (shuffled) pool order
+    pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut assigned: Vec<Option<usize>> = vec![None; slots.len()];
+    let mut used = vec![false; pool.len()];
+
+    // 1. Within tolerance, best fits first; 2. Leftover slots take the best of the rest
+    for within_tolerance in [true, false] {
+        for &(loss, slot_index, image_index) in &pairs {
+            if assigned[slot_index].is_some() || used[image_index] {
+                continue;
+            }
+            if within_tolerance && loss > tolerance {
+                continue;
+            }
+            assigned[slot_index] = Some(image_index);
+            used[image_index] = true;
+        }
+    }
+
+    let mut pool: Vec<Option<FileInfo>> = pool.into_iter().map(Some).collect();
+    assigned
+        .into_iter()
+        .flatten()
+        .filter_map(|image_index| pool[image_index].take())
+        .collect()
+}
+
+//----------------------------------------------------------------------------//
+//                                   Tests                                    //
+//----------------------------------------------------------------------------//
+
+#[cfg(test)]
+mod tests_matching {
+    use super::*;
+    use crate::FitMode;
+    use std::path::PathBuf;
+
+    fn image(name: &str, width: u64, height: u64) -> FileInfo {
+        FileInfo {
+            path: PathBuf::from(name),
+            dimension: Some(Dimension { width, height }),
+            ..FileInfo::default()
+        }
+    }
+
+    fn names(images: &[FileInfo]) -> Vec<&str> {
+        images.iter().filter_map(|f| f.path.to_str()).collect()
+    }
+
+    #[test]
+    fn test_match_images_by_aspect_ratio() {
+        let landscape = Dimension {
+            width: 3840,
+            height: 2160,
+        };
+        let portrait = Dimension {
+            width: 1080,
+            height: 1920,
+        };
+        assert_eq!(crop_loss(&landscape, &landscape), 0.0);
+        assert!(crop_loss(&portrait, &landscape) > 0.6);
+
+        // A portrait monitor, then a landscape monitor split in two stacked rows
+        let monitors = [
+            Monitor {
+                resolution: portrait.clone(),
+                ..Monitor::default()
+            },
+            Monitor {
+                picture_orientation: Orientation::Horizontal,
+                pictures_per_monitor: 2,
+                resolution: landscape.clone(),
+                ..Monitor::default()
+            },
+        ];
+
+        let pool = vec![
+            image("panorama", 6000, 1000),
+            image("wide", 3840, 2160),
+            image("phone", 1080, 1920),
+            image("ultrawide", 3440, 720),
+        ];
+
+        // Each row of the second monitor is 3840x1080 (32:9): the panoramas fit best
+        let matched = match_images(pool.clone(), &monitors, None);
+        assert_eq!(names(&matched), ["phone", "ultrawide", "panorama"]);
+
+        // Without a good enough candidate, a slot still takes the best remaining image
+        let matched = match_images(pool[..3].to_vec(), &monitors, Some(5));
+        assert_eq!(names(&matched), ["phone", "panorama", "wide"]);
+
+        // Spanned over three landscape screens (48:9), the panorama wins
+        let row = vec![monitors[1].clone(); 3];
+        let spanned = match_spanned(pool[..3].to_vec(), &row, &Orientation::Horizontal, None);
+        assert_eq!(names(&spanned), ["panorama"]);
+
+        // Fitted photos lose nothing to the crop: the shuffled order is kept
+        let fitted = [Monitor {
+            fit: FitMode::Fit,
+            ..monitors[0].clone()
+        }];
+        assert_eq!(
+            names(&match_images(pool.clone(), &fitted, None)),
+            ["panorama"]
+        );
+    }
+
+    #[test]
+    fn test_max_crop_turns_on_matching() {
+        let portrait = [Monitor {
+            resolution: Dimension {
+                width: 1080,
+                height: 1920,
+            },
+            ..Monitor::default()
+        }];
+        let pool = vec![image("panorama", 6000, 1000), image("phone", 1080, 1920)];
+
+        // Without matching, the shuffled order decides
+        let config = Config::default();
+        let selected = select_cycle_images(pool.clone(), 1, &portrait, &config);
+        assert_eq!(names(&selected), ["panorama"]);
+
+        // `--max-crop` alone is enough to pick the image fitting the portrait monitor
+        let config = Config {
+            max_crop: Some(25),
+            ..Config::default()
+        };
+        let selected = select_cycle_images(pool, 1, &portrait, &config);
+        assert_eq!(names(&selected), ["phone"]);
+    }
+}
@@ -1,8 +1,26 @@
+mod bezel;
 mod config;
+mod crop;
 mod dimension;
+mod directory;
+mod dynamic;
 mod fileinfo;
+mod fit;
+mod frame;
+mod history;
+mod layout;
+mod matching;
 mod monitors;
 mod orientation;
+mod output;
+mod profile;
+mod resample;
+mod schedule;
 mod state;
+mod sun;
 
-pub use self::{config::*, dimension::*, fileinfo::*, monitors::*, orientation::*, state::*};
+pub use self::{
+    bezel::*, config::*, crop::*, dimension::*, directory::*, dynamic::*, fileinfo::*, fit::*,
+    frame::*, history::*, layout::*, matching::*, monitors::*, orientation::*, output::*,
+    profile::*, resample::*, schedule::*, state::*, sun::*,
+};
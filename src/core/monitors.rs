@@ -1,12 +1,16 @@
-use crate::{Dimension, Orientation};
+use crate::{
+    CanvasBase, Config, CropMode, Dimension, FitMode, Layout, Orientation, Output,
+    ProceduralEffect, Transform, percent_chance,
+};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 /// Monitor properties
 ///
 /// Each monitor can have a diferent number of pictures (or images)
 ///
 /// Each monitor can have different pictures (or images) orientation
-#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Monitor {
     /// Indicates how the images are combined
     pub picture_orientation: Orientation,
@@ -14,6 +18,90 @@ pub struct Monitor {
     pub pictures_per_monitor: u8,
     /// Set the monitor resolution: "widthxheight"
     pub resolution: Dimension,
+    /// Output (connector) name this canvas is bound to, e.g. "DP-1".
+    ///
+    /// Unnamed monitors are matched to the remaining outputs by index.
+    #[serde(default)]
+    pub output: Option<String>,
+    /// Canvas the overlay is drawn on: source photos, or a generated base.
+    ///
+    /// Generated bases (solid, gradient, dark) need no images at all.
+    #[serde(default)]
+    pub base: CanvasBase,
+    /// Manifest of a dynamic wallpaper set shown instead of photos or the base.
+    ///
+    /// The frame matching the time of day (or the sun) is cross-faded each cycle.
+    #[serde(default)]
+    pub dynamic: Option<PathBuf>,
+    /// Overlay effect of this monitor, overriding the global `effect`.
+    ///
+    /// Use "none" to keep a monitor clean whatever the global effect is.
+    #[serde(default)]
+    pub effect: Option<ProceduralEffect>,
+    /// Chance (in percent, 0 to 100) that the overlay is drawn in a cycle [default: 100].
+    #[serde(default)]
+    pub effect_percent: Option<u8>,
+    /// How photos are cropped to their slot: "center" or "smart" (saliency search).
+    #[serde(default)]
+    pub crop: CropMode,
+    /// How photos are placed into their slot: fill, fit, blur, stretch, center or tile.
+    #[serde(default)]
+    pub fit: FitMode,
+    /// Colour of the bars left by the "fit" and "center" modes, as `[r, g, b]`.
+    #[serde(default)]
+    pub fit_color: [u8; 3],
+    /// How pictures are arranged: strips, grid, hero (one large plus thumbnails) or justified.
+    #[serde(default)]
+    pub layout: Layout,
+    /// Number of columns of the grid layout [default: as square as possible].
+    #[serde(default)]
+    pub columns: Option<u8>,
+    /// Space between neighbouring pictures, in pixels.
+    #[serde(default)]
+    pub gutter: u32,
+    /// Colour of the gutters and margin, as `[r, g, b]`.
+    #[serde(default)]
+    pub gutter_color: [u8; 3],
+    /// Space between the pictures and the border of the screen, in pixels.
+    #[serde(default)]
+    pub margin: u32,
+    /// Radius of the rounded corners of each picture, in pixels.
+    #[serde(default)]
+    pub corner_radius: u32,
+    /// Draw a soft drop shadow under each picture.
+    #[serde(default)]
+    pub shadow: bool,
+    /// Physical size of the screen area in millimetres, for spanned images [default: 96 DPI].
+    #[serde(default)]
+    pub physical_size: Option<Dimension>,
+    /// Gap between this screen and the next one (both bezels), in millimetres.
+    #[serde(default)]
+    pub bezel: u32,
+    /// Shift across the monitor orientation (down or right), in millimetres.
+    #[serde(default)]
+    pub offset: i32,
+    /// Top-left corner on the desktop, in pixels [default: detected, or along `monitor_orientation`].
+    #[serde(default)]
+    pub position: Option<Position>,
+    /// Scale factor of the output when `resolution` is given in logical pixels (e.g. 1.5).
+    ///
+    /// The canvas is rendered at `resolution` times `scale`, so HiDPI screens stay sharp.
+    #[serde(default)]
+    pub scale: Option<f64>,
+    /// Rotation of the output [default: detected, or normal].
+    ///
+    /// Rotated transforms (90, 270, ...) swap the width and height of the canvas.
+    #[serde(default)]
+    pub transform: Transform,
+}
+
+/// Top-left corner of a monitor in the global desktop layout, in pixels.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Position {
+    pub x: i64,
+    pub y: i64,
 }
 
 impl Default for Monitor {
@@ -22,11 +110,77 @@ impl Default for Monitor {
             picture_orientation: Orientation::Horizontal,
             pictures_per_monitor: 1,
             resolution: Dimension::default(),
+            output: None,
+            base: CanvasBase::Photo,
+            dynamic: None,
+            effect: None,
+            effect_percent: None,
+            crop: CropMode::Center,
+            fit: FitMode::Fill,
+            fit_color: [0, 0, 0],
+            layout: Layout::Strips,
+            columns: None,
+            gutter: 0,
+            gutter_color: [0, 0, 0],
+            margin: 0,
+            corner_radius: 0,
+            shadow: false,
+            physical_size: None,
+            bezel: 0,
+            offset: 0,
+            position: None,
+            scale: None,
+            transform: Transform::Normal,
         }
     }
 }
 
 impl Monitor {
+    /// Picks the concrete overlay of this monitor for one cycle.
+    ///
+    /// The monitor's own effect (or the global `effect`) is drawn with the chance
+    /// `effect_percent` (or the global `effects.percent`); meta-effects are then
+    /// picked according to the `effects.weights` table.
+    pub fn resolve_effect(&self, config: &Config) -> ProceduralEffect {
+        let effect = self.effect.unwrap_or(config.effect);
+
+        match self.effect_percent {
+            None => effect.resolve(&config.effects),
+            Some(percent) if effect != ProceduralEffect::None && percent_chance(percent) => {
+                effect.pick(&config.effects.weights)
+            }
+            Some(_) => ProceduralEffect::None,
+        }
+    }
+
+    /// Number of source images consumed by this monitor in each cycle.
+    ///
+    /// Monitors with a generated base or a dynamic set take no images.
+    pub fn pictures_needed(&self) -> usize {
+        if self.base.is_generated() || self.dynamic.is_some() {
+            0
+        } else {
+            usize::from(self.pictures_per_monitor)
+        }
+    }
+
+    /// Size of the canvas in physical pixels: `resolution` multiplied by `scale`,
+    /// with width and height swapped by a rotating `transform`.
+    pub fn physical_resolution(&self) -> Dimension {
+        let scale = self.scale.filter(|&scale| scale > 0.0).unwrap_or(1.0);
+        let width = (self.resolution.width as f64 * scale).round() as u64;
+        let height = (self.resolution.height as f64 * scale).round() as u64;
+
+        if self.transform.is_rotated() {
+            Dimension {
+                width: height,
+                height: width,
+            }
+        } else {
+            Dimension { width, height }
+        }
+    }
+
     pub fn flip(mut self) -> Self {
         match self.picture_orientation {
             Orientation::Horizontal => {
@@ -57,3 +211,506 @@ pub fn get_monitors(number: usize) -> Vec<Monitor> {
         })
         .collect()
 }
+
+/// Finds the detected output that belongs to each monitor.
+///
+/// Monitors with an `output` name are bound to the output with that name (or to
+/// nothing if it is not connected). Unnamed monitors take the remaining outputs
+/// in the order the detection tool printed them.
+///
+/// Returns, for each monitor, the index of its output in `outputs`.
+pub fn match_outputs(monitors: &[Monitor], outputs: &[Output]) -> Vec<Option<usize>> {
+    let mut claimed = vec![false; outputs.len()];
+
+    // 1. Named monitors claim their outputs first.
+    let mut matches: Vec<Option<usize>> = monitors
+        .iter()
+        .map(|monitor| {
+            let name = monitor.output.as_deref()?;
+            let index = outputs.iter().position(|output| output.name == name)?;
+            claimed[index] = true;
+            Some(index)
+        })
+        .collect();
+
+    // 2. Unnamed monitors fall back to the unclaimed outputs, by index.
+    let mut unclaimed = (0..outputs.len()).filter(|&index| !claimed[index]);
+    for (monitor, matched) in monitors.iter().zip(matches.iter_mut()) {
+        if monitor.output.is_none() {
+            *matched = unclaimed.next();
+        }
+    }
+
+    matches
+}
+
+/// Pairs every detected output with the index of the compiled canvas it should display.
+///
+/// Canvas `i` is the one compiled for `monitors[i]`. Outputs left over after matching
+/// (more screens than configured monitors) cycle through the unnamed monitors' canvases,
+/// or through all canvases if every monitor is named. The result follows `outputs` order.
+pub fn assign_canvases<'a>(
+    monitors: &[Monitor],
+    outputs: &'a [Output],
+) -> Vec<(usize, &'a Output)> {
+    let matches = match_outputs(monitors, outputs);
+
+    let mut canvas_of: Vec<Option<usize>> = vec![None; outputs.len()];
+    for (canvas, matched) in matches.iter().enumerate() {
+        if let Some(index) = matched {
+            canvas_of[*index] = Some(canvas);
+        }
+    }
+
+    let mut fallback: Vec<usize> = (0..monitors.len())
+        .filter(|&canvas| monitors[canvas].output.is_none())
+        .collect();
+    if fallback.is_empty() {
+        fallback = (0..monitors.len()).collect();
+    }
+
+    let mut leftover = fallback.iter().cycle();
+    outputs
+        .iter()
+        .zip(canvas_of)
+        .filter_map(|(output, canvas)| Some((canvas.or_else(|| leftover.next().copied())?, output)))
+        .collect()
+}
+
+/// Returns a copy of `monitors` with each resolution replaced by the native mode size
+/// of its detected output (see [`match_outputs`]), and each missing position filled
+/// with the position of that output, ready to be rendered like [`apply_scale`].
+///
+/// Mode sizes are physical pixels, so a configured `scale` is dropped, while the
+/// output `transform` rotates the canvas. Detected positions are logical, like the
+/// configured ones: along each axis, every stretch covered by an output is converted
+/// with that output's scale, so screens of mixed scales still touch on the spanned image.
+///
+/// Monitors without a matching output, or whose output did not report a mode,
+/// keep their configured resolution and scale. Positions are only taken from tools
+/// that report a layout (several outputs all at 0,0 carry no position information).
+pub fn apply_detected_geometry(monitors: &[Monitor], outputs: &[Output]) -> Vec<Monitor> {
+    let positioned = outputs.len() == 1 || outputs.iter().any(|o| o.x != 0 || o.y != 0);
+
+    // Logical extent of every output along each axis, with its scale
+    let (mut columns, mut rows) = (Vec::new(), Vec::new());
+    for output in outputs {
+        let Some(resolution) = output.resolution() else {
+            continue;
+        };
+        let scale = Some(output.scale)
+            .filter(|&scale| scale > 0.0)
+            .unwrap_or(1.0);
+        let (width, height) = if output.transform.is_rotated() {
+            (resolution.height, resolution.width)
+        } else {
+            (resolution.width, resolution.height)
+        };
+        let logical = |pixels: u64| (pixels as f64 / scale).round() as i64;
+        columns.push((output.x, output.x + logical(width), scale));
+        rows.push((output.y, output.y + logical(height), scale));
+    }
+
+    let detected: Vec<Monitor> = monitors
+        .iter()
+        .zip(match_outputs(monitors, outputs))
+        .map(|(monitor, matched)| {
+            let mut monitor = monitor.clone();
+            if let Some(output) = matched.map(|index| &outputs[index]) {
+                if let Some(resolution) = output.resolution() {
+                    monitor.resolution = resolution;
+                    monitor.scale = None;
+                    monitor.transform = output.transform;
+                }
+                if monitor.position.is_none() && positioned {
+                    monitor.position = Some(Position {
+                        x: output.x,
+                        y: output.y,
+                    });
+                }
+            }
+            monitor
+        })
+        .collect();
+
+    // A 2.0 panel 1440 logical pixels wide pushes the next screen 2880 pixels away
+    scale_monitors(&detected, |position| Position {
+        x: measure_axis(position.x, &columns, 1.0).round() as i64,
+        y: measure_axis(position.y, &rows, 1.0).round() as i64,
+    })
+}
+
+/// Returns a copy of `monitors` whose resolutions are the physical canvas sizes
+/// (see [`Monitor::physical_resolution`]), ready to be rendered.
+///
+/// Configured positions are multiplied by the largest monitor `scale`, so logical
+/// desktop coordinates keep matching the scaled canvases.
+pub fn apply_scale(monitors: &[Monitor]) -> Vec<Monitor> {
+    let density = monitors
+        .iter()
+        .filter_map(|monitor| monitor.scale)
+        .filter(|&scale| scale > 0.0)
+        .fold(1.0, f64::max);
+
+    scale_monitors(monitors, |position| Position {
+        x: (position.x as f64 * density).round() as i64,
+        y: (position.y as f64 * density).round() as i64,
+    })
+}
+
+/// Converts `monitors` to physical canvases, moving their logical positions with `to_physical`.
+fn scale_monitors(
+    monitors: &[Monitor],
+    to_physical: impl Fn(Position) -> Position,
+) -> Vec<Monitor> {
+    monitors
+        .iter()
+        .map(|monitor| Monitor {
+            resolution: monitor.physical_resolution(),
+            position: monitor.position.map(&to_physical),
+            scale: None,
+            transform: Transform::Normal,
+            ..monitor.clone()
+        })
+        .collect()
+}
+
+/// Measures one axis from the origin to `coordinate`, converting each stretch with
+/// the factor of the span covering it.
+///
+/// `spans` holds the `(start, end, factor)` of each screen on that axis (e.g. its
+/// scale, or its pixel pitch in millimetres). Where screens overlap, the largest
+/// factor wins; stretches outside of every span use `outside`.
+pub fn measure_axis(coordinate: i64, spans: &[(i64, i64, f64)], outside: f64) -> f64 {
+    let (from, to, sign) = if coordinate >= 0 {
+        (0, coordinate, 1.0)
+    } else {
+        (coordinate, 0, -1.0)
+    };
+
+    let mut cuts: Vec<i64> = spans
+        .iter()
+        .flat_map(|&(start, end, _)| [start, end])
+        .filter(|&cut| from < cut && cut < to)
+        .chain([from, to])
+        .collect();
+    cuts.sort_unstable();
+    cuts.dedup();
+
+    let length: f64 = cuts
+        .windows(2)
+        .map(|pair| {
+            let factor = spans
+                .iter()
+                .filter(|&&(start, end, _)| start <= pair[0] && pair[1] <= end)
+                .map(|&(_, _, factor)| factor)
+                .reduce(f64::max)
+                .unwrap_or(outside);
+            (pair[1] - pair[0]) as f64 * factor
+        })
+        .sum();
+
+    sign * length
+}
+
+/// Places every monitor canvas on the spanned desktop image.
+///
+/// When every monitor has a `position`, the canvases are placed there (shifted so
+/// that the top-left monitor starts at 0,0), which supports L-shaped and offset
+/// setups. Otherwise they are lined up along `orientation`.
+///
+/// Returns the top-left corner of each canvas, in monitor order, and the size of the desktop.
+pub fn desktop_layout(
+    monitors: &[Monitor],
+    orientation: &Orientation,
+) -> (Vec<Position>, Dimension) {
+    let configured: Option<Vec<Position>> = monitors.iter().map(|m| m.position).collect();
+
+    let positions: Vec<Position> = match configured {
+        Some(positions) => {
+            let min_x = positions.iter().map(|p| p.x).min().unwrap_or(0);
+            let min_y = positions.iter().map(|p| p.y).min().unwrap_or(0);
+            positions
+                .iter()
+                .map(|p| Position {
+                    x: p.x - min_x,
+                    y: p.y - min_y,
+                })
+                .collect()
+        }
+        None => {
+            let mut along = 0;
+            monitors
+                .iter()
+                .map(|monitor| {
+                    let corner = match orientation {
+                        Orientation::Horizontal => Position { x: along, y: 0 },
+                        Orientation::Vertical => Position { x: 0, y: along },
+                    };
+                    along += match orientation {
+                        Orientation::Horizontal => monitor.resolution.width as i64,
+                        Orientation::Vertical => monitor.resolution.height as i64,
+                    };
+                    corner
+                })
+                .collect()
+        }
+    };
+
+    let size = positions.iter().zip(monitors).fold(
+        Dimension {
+            width: 0,
+            height: 0,
+        },
+        |size, (corner, monitor)| Dimension {
+            width: size.width.max(corner.x as u64 + monitor.resolution.width),
+            height: size.height.max(corner.y as u64 + monitor.resolution.height),
+        },
+    );
+
+    (positions, size)
+}
+
+//----------------------------------------------------------------------------//
+//                                   Tests                                    //
+//----------------------------------------------------------------------------//
+
+#[cfg(test)]
+mod tests_monitors {
+    use super::*;
+
+    #[test]
+    fn test_apply_detected_geometry() {
+        let monitors = get_monitors(3);
+
+        let mut ultrawide = Output::named("DP-1");
+        ultrawide.width = 3440;
+        ultrawide.height = 1440;
+        let unknown = Output::named("DP-2");
+
+        let resolved = apply_detected_geometry(&monitors, &[ultrawide, unknown]);
+
+        assert_eq!(resolved.len(), 3);
+        assert_eq!(
+            resolved[0].resolution,
+            Dimension {
+                width: 3440,
+                height: 1440
+            }
+        );
+        // No reported mode / no output at all: keep the configured value.
+        assert_eq!(resolved[1].resolution, Dimension::default());
+        assert_eq!(resolved[2].resolution, Dimension::default());
+        // Layout settings are preserved.
+        assert_eq!(
+            resolved[0].picture_orientation,
+            monitors[0].picture_orientation
+        );
+        // Two outputs both at 0,0 carry no layout: positions stay unset.
+        assert_eq!(resolved[0].position, None);
+    }
+
+    #[test]
+    fn test_scaled_and_rotated_monitors() {
+        // A 1.5x laptop configured with its logical size renders at its native size
+        let laptop = Monitor {
+            resolution: Dimension {
+                width: 1920,
+                height: 1200,
+            },
+            scale: Some(1.5),
+            position: Some(Position { x: 0, y: 0 }),
+            ..Monitor::default()
+        };
+        let portrait = Monitor {
+            resolution: Dimension {
+                width: 1920,
+                height: 1080,
+            },
+            transform: Transform::Rotate270,
+            position: Some(Position { x: 1920, y: 0 }),
+            ..Monitor::default()
+        };
+
+        let physical = apply_scale(&[laptop, portrait]);
+        assert_eq!(
+            physical[0].resolution,
+            Dimension {
+                width: 2880,
+                height: 1800
+            }
+        );
+        assert_eq!(
+            physical[1].resolution,
+            Dimension {
+                width: 1080,
+                height: 1920
+            }
+        );
+        assert_eq!(physical[1].position, Some(Position { x: 2880, y: 0 }));
+        assert_eq!(physical[1].transform, Transform::Normal);
+
+        // Detected modes are already physical: only the transform rotates them
+        let mut panel = Output::named("eDP-1");
+        (panel.width, panel.height, panel.scale) = (2880, 1800, 1.5);
+        let mut side = Output::named("DP-1");
+        (side.width, side.height, side.x) = (1920, 1080, 1920);
+        side.transform = Transform::Rotate90;
+
+        let mut monitors = get_monitors(3);
+        monitors[2].scale = Some(1.5);
+        let detected = apply_detected_geometry(&monitors, &[panel, side]);
+        assert_eq!(detected[0].resolution.width, 2880);
+        assert_eq!(
+            detected[1].resolution,
+            Dimension {
+                width: 1080,
+                height: 1920
+            }
+        );
+        // Scaled once, even though the unmatched monitor keeps its own scale
+        assert_eq!(detected[1].position, Some(Position { x: 2880, y: 0 }));
+        assert_eq!(detected[2].scale, None);
+    }
+
+    #[test]
+    fn test_mixed_scale_positions() {
+        // A 2.0 laptop panel (1440x900 logical) next to a 1.0 external screen
+        let mut laptop = Output::named("eDP-1");
+        (laptop.width, laptop.height, laptop.scale) = (2880, 1800, 2.0);
+        let mut external = Output::named("DP-1");
+        (external.width, external.height, external.x) = (1920, 1080, 1440);
+
+        let detected = apply_detected_geometry(&get_monitors(2), &[laptop.clone(), external]);
+        assert_eq!(detected[0].position, Some(Position { x: 0, y: 0 }));
+        assert_eq!(detected[1].position, Some(Position { x: 2880, y: 0 }));
+
+        // External screen first: the panel starts where the 1.0 screen ends
+        let mut external = Output::named("DP-1");
+        (external.width, external.height) = (1920, 1080);
+        laptop.x = 1920;
+
+        let detected = apply_detected_geometry(&get_monitors(2), &[external, laptop]);
+        assert_eq!(detected[1].position, Some(Position { x: 1920, y: 0 }));
+
+        let (corners, size) = desktop_layout(&detected, &Orientation::Horizontal);
+        assert_eq!(corners[1], Position { x: 1920, y: 0 });
+        assert_eq!((size.width, size.height), (4800, 1800));
+    }
+
+    #[test]
+    fn test_desktop_layout_with_positions() {
+        let monitor = |width, height, position| Monitor {
+            resolution: Dimension { width, height },
+            position,
+            ..Monitor::default()
+        };
+
+        // Portrait screen on the left, two landscape screens vertically centred beside it
+        let l_shaped = [
+            monitor(1080, 1920, Some(Position { x: -1080, y: 0 })),
+            monitor(1920, 1080, Some(Position { x: 0, y: 420 })),
+            monitor(1920, 1080, Some(Position { x: 1920, y: 420 })),
+        ];
+        let (corners, size) = desktop_layout(&l_shaped, &Orientation::Horizontal);
+        assert_eq!(corners[0], Position { x: 0, y: 0 });
+        assert_eq!(corners[2], Position { x: 3000, y: 420 });
+        assert_eq!((size.width, size.height), (4920, 1920));
+
+        // A single unpositioned monitor: lined up along the orientation
+        let mut mixed = l_shaped.clone();
+        mixed[1].position = None;
+        let (corners, size) = desktop_layout(&mixed, &Orientation::Horizontal);
+        assert_eq!(corners[2], Position { x: 3000, y: 0 });
+        assert_eq!((size.width, size.height), (4920, 1920));
+
+        // Detected positions fill the missing ones
+        let mut left = Output::named("DP-1");
+        (left.width, left.height) = (1080, 1920);
+        let mut right = Output::named("DP-2");
+        (right.x, right.y) = (1080, 420);
+        let detected = apply_detected_geometry(&mixed[..2], &[left, right]);
+        assert_eq!(detected[0].position, Some(Position { x: -1080, y: 0 }));
+        assert_eq!(detected[1].position, Some(Position { x: 1080, y: 420 }));
+    }
+
+    #[test]
+    fn test_assign_canvases_by_name() {
+        let outputs = vec![
+            Output::named("HDMI-A-1"),
+            Output::named("DP-1"),
+            Output::named("DP-2"),
+        ];
+
+        let portrait = Monitor {
+            output: Some("DP-1".to_string()),
+            ..Monitor::default()
+        };
+        let unplugged = Monitor {
+            output: Some("eDP-1".to_string()),
+            ..Monitor::default()
+        };
+        let monitors = vec![unplugged, Monitor::default(), portrait];
+
+        // Named monitors claim their outputs regardless of print order.
+        assert_eq!(
+            match_outputs(&monitors, &outputs),
+            vec![None, Some(0), Some(1)]
+        );
+
+        let pairs: Vec<(usize, &str)> = assign_canvases(&monitors, &outputs)
+            .into_iter()
+            .map(|(canvas, output)| (canvas, output.name.as_str()))
+            .collect();
+
+        // DP-2 is left over and reuses the only unnamed canvas.
+        assert_eq!(pairs, vec![(1, "HDMI-A-1"), (2, "DP-1"), (1, "DP-2")]);
+    }
+
+    #[test]
+    fn test_assign_canvases_unnamed_keeps_index_order() {
+        let outputs = vec![Output::named("DP-1"), Output::named("DP-2")];
+        let monitors = get_monitors(2);
+
+        let canvases: Vec<usize> = assign_canvases(&monitors, &outputs)
+            .into_iter()
+            .map(|(canvas, _)| canvas)
+            .collect();
+        assert_eq!(canvases, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_resolve_effect() {
+        let clean = Monitor {
+            effect: Some(ProceduralEffect::None),
+            ..Monitor::default()
+        };
+        let side = Monitor {
+            effect: Some(ProceduralEffect::CosmicAurora),
+            ..Monitor::default()
+        };
+        let never = Monitor {
+            effect_percent: Some(0),
+            ..Monitor::default()
+        };
+        let inherit = Monitor::default();
+
+        let config = Config {
+            effect: ProceduralEffect::JuliaSet,
+            ..Config::default()
+        };
+        assert_eq!(clean.resolve_effect(&config), ProceduralEffect::None);
+        assert_eq!(side.resolve_effect(&config), ProceduralEffect::CosmicAurora);
+        assert_eq!(never.resolve_effect(&config), ProceduralEffect::None);
+        assert_eq!(inherit.resolve_effect(&config), ProceduralEffect::JuliaSet);
+
+        let fractal = Config {
+            effect: ProceduralEffect::Fractal,
+            ..Config::default()
+        };
+        assert!(matches!(
+            inherit.resolve_effect(&fractal),
+            ProceduralEffect::JuliaSet | ProceduralEffect::Mandelbrot
+        ));
+    }
+}
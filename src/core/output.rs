@@ -0,0 +1,163 @@
+use crate::{Dimension, WallSwitchError, WallSwitchResult};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// Rotation and/or reflection applied by the compositor to a physical output.
+///
+/// Mirrors the Wayland `wl_output.transform` enumeration, which is also what
+/// `hyprctl`, `niri` and `wlr-randr` report.
+///
+/// Default: Normal.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Transform {
+    #[default]
+    Normal,
+    #[serde(rename = "90")]
+    Rotate90,
+    #[serde(rename = "180")]
+    Rotate180,
+    #[serde(rename = "270")]
+    Rotate270,
+    Flipped,
+    #[serde(rename = "flipped-90")]
+    Flipped90,
+    #[serde(rename = "flipped-180")]
+    Flipped180,
+    #[serde(rename = "flipped-270")]
+    Flipped270,
+}
+
+impl Transform {
+    /// Returns true if the transform swaps the width and height of the output.
+    pub fn is_rotated(self) -> bool {
+        matches!(
+            self,
+            Self::Rotate90 | Self::Rotate270 | Self::Flipped90 | Self::Flipped270
+        )
+    }
+}
+
+impl fmt::Display for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Self::Normal => "normal",
+            Self::Rotate90 => "90",
+            Self::Rotate180 => "180",
+            Self::Rotate270 => "270",
+            Self::Flipped => "flipped",
+            Self::Flipped90 => "flipped-90",
+            Self::Flipped180 => "flipped-180",
+            Self::Flipped270 => "flipped-270",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl FromStr for Transform {
+    type Err = WallSwitchError;
+
+    /// Accepts both the textual names (`normal`, `90`, `flipped-270`, ...) and the
+    /// numeric `wl_output.transform` values (`0` to `7`) printed by `hyprctl`.
+    fn from_str(s: &str) -> WallSwitchResult<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "0" | "normal" => Ok(Self::Normal),
+            "1" | "90" => Ok(Self::Rotate90),
+            "2" | "180" => Ok(Self::Rotate180),
+            "3" | "270" => Ok(Self::Rotate270),
+            "4" | "flipped" => Ok(Self::Flipped),
+            "5" | "flipped-90" => Ok(Self::Flipped90),
+            "6" | "flipped-180" => Ok(Self::Flipped180),
+            "7" | "flipped-270" => Ok(Self::Flipped270),
+            _ => Err(WallSwitchError::InvalidValue {
+                arg: "transform".to_string(),
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// A physical display output as reported by the compositor or the kernel.
+///
+/// Geometry fields are zero when the detection tool does not expose them
+/// (e.g. XFCE property paths or DRM connectors without a mode list).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+    /// Connector name (e.g. "DP-1", "HDMI-A-1", "eDP-1").
+    pub name: String,
+    /// Width of the current mode in physical pixels.
+    pub width: u64,
+    /// Height of the current mode in physical pixels.
+    pub height: u64,
+    /// Horizontal position in the global (logical) desktop layout.
+    pub x: i64,
+    /// Vertical position in the global (logical) desktop layout.
+    pub y: i64,
+    /// Fractional scale factor applied by the compositor.
+    pub scale: f64,
+    /// Rotation/reflection applied by the compositor.
+    pub transform: Transform,
+}
+
+impl Output {
+    /// Creates an output that only carries a name, with unknown geometry.
+    pub fn named(name: impl Into<String>) -> Self {
+        Output {
+            name: name.into(),
+            width: 0,
+            height: 0,
+            x: 0,
+            y: 0,
+            scale: 1.0,
+            transform: Transform::Normal,
+        }
+    }
+
+    /// Returns the native mode size, or `None` if the detection tool did not report it.
+    pub fn resolution(&self) -> Option<Dimension> {
+        (self.width > 0 && self.height > 0).then_some(Dimension {
+            width: self.width,
+            height: self.height,
+        })
+    }
+}
+
+//----------------------------------------------------------------------------//
+//                                   Tests                                    //
+//----------------------------------------------------------------------------//
+
+#[cfg(test)]
+mod tests_output {
+    use super::*;
+
+    #[test]
+    fn test_transform_parsing() {
+        assert_eq!("0".parse::<Transform>().unwrap(), Transform::Normal);
+        assert_eq!("normal".parse::<Transform>().unwrap(), Transform::Normal);
+        assert_eq!("1".parse::<Transform>().unwrap(), Transform::Rotate90);
+        assert_eq!(
+            "Flipped-270".parse::<Transform>().unwrap(),
+            Transform::Flipped270
+        );
+        assert!("sideways".parse::<Transform>().is_err());
+
+        assert!(Transform::Rotate90.is_rotated());
+        assert!(!Transform::Rotate180.is_rotated());
+    }
+
+    #[test]
+    fn test_output_resolution() {
+        let mut output = Output::named("DP-1");
+        assert_eq!(output.resolution(), None);
+
+        output.width = 2560;
+        output.height = 1440;
+        assert_eq!(
+            output.resolution(),
+            Some(Dimension {
+                width: 2560,
+                height: 1440
+            })
+        );
+    }
+}
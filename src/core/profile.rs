@@ -0,0 +1,105 @@
+//! Named profiles: sets of settings layered over the base configuration.
+//!
+//! ```json
+//! "profile": "work",
+//! "profiles": {
+//!   "work": { "directories": ["/home/user/Pictures/Landscapes"], "effect": "none" },
+//!   "night": { "effect": "starfield", "interval": 3600, "max_dimension": 4000 }
+//! }
+//! ```
+//!
+//! The active profile is chosen by `"profile"` (or `--profile`) and can be switched
+//! at runtime with `wallswitch ctl profile <name>`. Settings missing from a profile
+//! keep their base value, and command-line flags still take precedence.
+
+use crate::{Config, Directory, ProceduralEffect};
+use serde::{Deserialize, Serialize};
+
+/// Settings overridden by a named profile; `None` keeps the base value.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    /// Directories containing image files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directories: Option<Vec<Directory>>,
+    /// Image file extensions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<Vec<String>>,
+    /// Interval (in seconds) between each wallpaper displayed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval: Option<u64>,
+    /// Minimum dimension
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_dimension: Option<u64>,
+    /// Maximum dimension
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_dimension: Option<u64>,
+    /// Minimum file size
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_size: Option<u64>,
+    /// Maximum file size
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<u64>,
+    /// Procedural overlay effect
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effect: Option<ProceduralEffect>,
+}
+
+impl Profile {
+    /// Returns `config` with the settings of this profile applied.
+    pub fn apply(&self, mut config: Config) -> Config {
+        if let Some(directories) = &self.directories {
+            config.directories = directories.clone();
+        }
+        if let Some(extensions) = &self.extensions {
+            config.extensions = extensions.clone();
+        }
+        if let Some(interval) = self.interval {
+            config.interval = interval;
+        }
+        if let Some(min_dimension) = self.min_dimension {
+            config.min_dimension = min_dimension;
+        }
+        if let Some(max_dimension) = self.max_dimension {
+            config.max_dimension = max_dimension;
+        }
+        if let Some(min_size) = self.min_size {
+            config.min_size = min_size;
+        }
+        if let Some(max_size) = self.max_size {
+            config.max_size = max_size;
+        }
+        if let Some(effect) = self.effect {
+            config.effect = effect;
+        }
+
+        config
+    }
+}
+
+//----------------------------------------------------------------------------//
+//                                   Tests                                    //
+//----------------------------------------------------------------------------//
+
+#[cfg(test)]
+mod tests_profile {
+    use super::*;
+
+    #[test]
+    fn test_profile_overrides() {
+        let night: Profile =
+            serde_json::from_str(r#"{ "effect": "starfield", "interval": 3600 }"#).unwrap();
+
+        let base = Config {
+            interval: 600,
+            min_dimension: 1080,
+            ..Config::default()
+        };
+        let config = night.apply(base.clone());
+
+        assert_eq!(config.effect, ProceduralEffect::Starfield);
+        assert_eq!(config.interval, 3600);
+        // Settings missing from the profile keep their base value
+        assert_eq!(config.min_dimension, 1080);
+        assert_eq!(config.directories, base.directories);
+    }
+}
@@ -0,0 +1,216 @@
+//! Resampling of photos to the size of their slot on the canvas.
+//!
+//! Downscaling an 8K photo to a monitor with a soft filter in sRGB space loses
+//! detail and darkens fine bright structures (stars, foliage against the sky),
+//! because sRGB values are averaged instead of light intensities:
+//!
+//! ```text
+//! sRGB:          mean(0, 255)                = 128   (too dark)
+//! linear light:  sqrt(mean(0^2, 1^2)) * 255  = 180
+//! ```
+//!
+//! `linear` resamples with the `gamma2`/`ungamma2` approximation of [`ColorRGB`],
+//! and `sharpen` restores the crispness lost by the filter with an unsharp mask.
+
+use crate::ColorRGB;
+use clap::ValueEnum;
+use image::{DynamicImage, Rgb, Rgb32FImage, RgbImage, imageops, imageops::FilterType};
+use serde::{Deserialize, Serialize};
+
+/// Differences below this level (0 to 255) are not sharpened, so flat areas keep no noise.
+const SHARPEN_THRESHOLD: i32 = 2;
+
+/// Interpolation filter used to scale photos.
+#[derive(
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ResampleFilter {
+    /// Nearest neighbour: blocky, but keeps pixel art crisp.
+    #[value(name = "nearest")]
+    Nearest,
+
+    /// Linear (tent) filter: fast and slightly soft.
+    #[value(name = "triangle")]
+    #[default]
+    Triangle,
+
+    /// Cubic filter: sharper than triangle.
+    #[value(name = "catmullrom")]
+    CatmullRom,
+
+    /// Gaussian filter: smooth, without ringing.
+    #[value(name = "gaussian")]
+    Gaussian,
+
+    /// Lanczos with a window of 3: sharpest, best for large downscales.
+    #[value(name = "lanczos3")]
+    Lanczos3,
+}
+
+impl From<ResampleFilter> for FilterType {
+    fn from(filter: ResampleFilter) -> Self {
+        match filter {
+            ResampleFilter::Nearest => FilterType::Nearest,
+            ResampleFilter::Triangle => FilterType::Triangle,
+            ResampleFilter::CatmullRom => FilterType::CatmullRom,
+            ResampleFilter::Gaussian => FilterType::Gaussian,
+            ResampleFilter::Lanczos3 => FilterType::Lanczos3,
+        }
+    }
+}
+
+/// How photos are scaled to their slot.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Resampling {
+    /// Interpolation filter: nearest, triangle, catmullrom, gaussian or lanczos3.
+    #[serde(default)]
+    pub filter: ResampleFilter,
+    /// Resample in linear light instead of sRGB (uses about 4 times more memory).
+    #[serde(default)]
+    pub linear: bool,
+    /// Gaussian sigma (in pixels) of the unsharp mask applied after a downscale; 0 disables it.
+    #[serde(default)]
+    pub sharpen: f32,
+}
+
+impl Default for Resampling {
+    fn default() -> Self {
+        Self {
+            filter: ResampleFilter::Triangle,
+            linear: false,
+            sharpen: 0.0,
+        }
+    }
+}
+
+impl Resampling {
+    /// Scales `img` to exactly `width` x `height`, ignoring its aspect ratio.
+    pub fn resize_exact(&self, img: &DynamicImage, width: u32, height: u32) -> RgbImage {
+        let (width, height) = (width.max(1), height.max(1));
+        let filter = FilterType::from(self.filter);
+
+        let resized = if self.linear {
+            from_linear(&imageops::resize(&to_linear(img), width, height, filter))
+        } else {
+            img.resize_exact(width, height, filter).to_rgb8()
+        };
+
+        if self.sharpen > 0.0 && (width < img.width() || height < img.height()) {
+            imageops::unsharpen(&resized, self.sharpen, SHARPEN_THRESHOLD)
+        } else {
+            resized
+        }
+    }
+
+    /// Scales `img` to the largest size that fits inside `width` x `height`,
+    /// keeping its aspect ratio.
+    pub fn resize(&self, img: &DynamicImage, width: u32, height: u32) -> RgbImage {
+        let (src_w, src_h) = (
+            f64::from(img.width().max(1)),
+            f64::from(img.height().max(1)),
+        );
+        let ratio = (f64::from(width) / src_w).min(f64::from(height) / src_h);
+
+        self.resize_exact(
+            img,
+            (src_w * ratio).round() as u32,
+            (src_h * ratio).round() as u32,
+        )
+    }
+}
+
+/// Converts `img` to linear light (squared sRGB values, see [`ColorRGB::gamma2`]).
+fn to_linear(img: &DynamicImage) -> Rgb32FImage {
+    let rgb = img.to_rgb8();
+    let mut linear = Rgb32FImage::new(rgb.width(), rgb.height());
+
+    for (source, target) in rgb.pixels().zip(linear.pixels_mut()) {
+        let [red, green, blue] = ColorRGB::from_slice(&source.0).gamma2().to_array();
+        *target = Rgb([red as f32, green as f32, blue as f32]);
+    }
+
+    linear
+}
+
+/// Converts a linear light image back to 8-bit sRGB, rounding to the nearest level.
+fn from_linear(linear: &Rgb32FImage) -> RgbImage {
+    let mut rgb = RgbImage::new(linear.width(), linear.height());
+
+    for (source, target) in linear.pixels().zip(rgb.pixels_mut()) {
+        let [red, green, blue] = source.0.map(f64::from);
+        let color = ColorRGB::new(red, green, blue).clamp_bounds().ungamma2();
+        target.0 = color
+            .to_array()
+            .map(|channel| (channel * 255.0).round() as u8);
+    }
+
+    rgb
+}
+
+//----------------------------------------------------------------------------//
+//                                   Tests                                    //
+//----------------------------------------------------------------------------//
+
+#[cfg(test)]
+mod tests_resample {
+    use super::*;
+
+    /// Black and white vertical lines, one pixel wide.
+    fn stripes() -> DynamicImage {
+        let img = RgbImage::from_fn(64, 64, |x, _| {
+            if x % 2 == 0 {
+                Rgb([0, 0, 0])
+            } else {
+                Rgb([255, 255, 255])
+            }
+        });
+        DynamicImage::ImageRgb8(img)
+    }
+
+    #[test]
+    fn test_linear_light_keeps_brightness() {
+        let srgb = Resampling::default().resize_exact(&stripes(), 8, 8);
+        let linear = Resampling {
+            linear: true,
+            ..Resampling::default()
+        }
+        .resize_exact(&stripes(), 8, 8);
+
+        // Averaging sRGB values darkens the lines; averaging light does not
+        let level = |img: &RgbImage| i32::from(img.get_pixel(4, 4).0[0]);
+        assert!((level(&srgb) - 128).abs() <= 2, "{}", level(&srgb));
+        assert!((level(&linear) - 180).abs() <= 2, "{}", level(&linear));
+    }
+
+    #[test]
+    fn test_resize_keeps_aspect_ratio() {
+        let resampling = Resampling {
+            filter: ResampleFilter::Lanczos3,
+            sharpen: 1.0,
+            ..Resampling::default()
+        };
+        let panorama = DynamicImage::ImageRgb8(RgbImage::new(400, 100));
+
+        assert_eq!(
+            resampling.resize(&panorama, 200, 200).dimensions(),
+            (200, 50)
+        );
+        assert_eq!(
+            resampling.resize_exact(&panorama, 30, 20).dimensions(),
+            (30, 20)
+        );
+        assert_eq!(FilterType::from(resampling.filter), FilterType::Lanczos3);
+    }
+}
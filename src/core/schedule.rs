@@ -0,0 +1,371 @@
+//! Time-of-day and sun-position schedules.
+//!
+//! ```json
+//! "location": { "latitude": 48.85, "longitude": 2.35 },
+//! "schedule": [
+//!   { "from": "sunset", "to": "sunrise", "profile": "night" },
+//!   { "from": "09:00", "to": "18:00", "effect": "none", "interval": 3600 }
+//! ]
+//! ```
+//!
+//! A rule is active from `from` (inclusive) to `to` (exclusive), wrapping past
+//! midnight when `to` comes first. Times are local clock times (`"HH:MM"`) or
+//! `"sunrise"`/`"sunset"`, optionally shifted by minutes (`"sunset-30"`), computed
+//! offline from `location`. The first active rule applies its `profile`, then its
+//! own overrides (the same keys as a profile); command-line flags still win.
+
+use crate::{Daylight, Location, Profile, WallSwitchError, WallSwitchResult};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    process::Command,
+    str::FromStr,
+    sync::{Mutex, PoisonError},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Time zone offsets only change on a quarter hour (UTC), so one reading lasts that long.
+const SECONDS_PER_OFFSET: i64 = 900;
+
+/// Start or end of a schedule rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum TimeOfDay {
+    /// Local clock time, in minutes after midnight.
+    Clock(u32),
+    /// Minutes after sunrise (before it, if negative).
+    Sunrise(i32),
+    /// Minutes after sunset (before it, if negative).
+    Sunset(i32),
+}
+
+impl TimeOfDay {
+    /// Returns true if the time depends on the position of the sun.
+    pub fn uses_sun(&self) -> bool {
+        !matches!(self, Self::Clock(_))
+    }
+
+    /// Seconds after local midnight (0 to 86400) at which this time falls on the day of `now`.
+    ///
+    /// Without a sunrise (polar night) the sun rises at the end of the day and sets at
+    /// its start, so `sunset`..`sunrise` covers the whole day; polar day is the opposite.
+    pub fn resolve(&self, now: LocalTime, daylight: Option<Daylight>) -> Option<i64> {
+        let local = |timestamp: i64, minutes: i32| {
+            (timestamp + i64::from(minutes) * 60 + now.utc_offset).rem_euclid(SECONDS_PER_DAY)
+        };
+
+        Some(match (*self, daylight) {
+            (Self::Clock(minutes), _) => i64::from(minutes) * 60,
+            (_, None) => return None,
+            (Self::Sunrise(minutes), Some(Daylight::Day { sunrise, .. })) => {
+                local(sunrise, minutes)
+            }
+            (Self::Sunset(minutes), Some(Daylight::Day { sunset, .. })) => local(sunset, minutes),
+            (Self::Sunrise(_), Some(Daylight::AlwaysUp))
+            | (Self::Sunset(_), Some(Daylight::AlwaysDown)) => 0,
+            (Self::Sunrise(_), Some(Daylight::AlwaysDown))
+            | (Self::Sunset(_), Some(Daylight::AlwaysUp)) => SECONDS_PER_DAY,
+        })
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (event, minutes) = match self {
+            Self::Clock(minutes) => return write!(f, "{:02}:{:02}", minutes / 60, minutes % 60),
+            Self::Sunrise(minutes) => ("sunrise", *minutes),
+            Self::Sunset(minutes) => ("sunset", *minutes),
+        };
+
+        match minutes {
+            0 => write!(f, "{event}"),
+            _ => write!(f, "{event}{minutes:+}"),
+        }
+    }
+}
+
+impl FromStr for TimeOfDay {
+    type Err = WallSwitchError;
+
+    /// Parses `"HH:MM"`, `"sunrise"`, `"sunset"` or a shifted event like `"sunrise+45"`.
+    fn from_str(s: &str) -> WallSwitchResult<Self> {
+        let invalid = || WallSwitchError::InvalidValue {
+            arg: "schedule".to_string(),
+            value: s.to_string(),
+        };
+        let trimmed = s.trim();
+
+        for (event, variant) in [
+            ("sunrise", Self::Sunrise as fn(i32) -> Self),
+            ("sunset", Self::Sunset),
+        ] {
+            if let Some(shift) = trimmed.strip_prefix(event) {
+                let minutes = match shift.trim() {
+                    "" => 0,
+                    shift => shift.parse().map_err(|_| invalid())?,
+                };
+                return Ok(variant(minutes));
+            }
+        }
+
+        let (hours, minutes) = trimmed.split_once(':').ok_or_else(invalid)?;
+        let hours: u32 = hours.parse().map_err(|_| invalid())?;
+        let minutes: u32 = minutes.parse().map_err(|_| invalid())?;
+
+        if hours > 24 || minutes > 59 || hours * 60 + minutes > 24 * 60 {
+            return Err(invalid());
+        }
+
+        Ok(Self::Clock(hours * 60 + minutes))
+    }
+}
+
+impl TryFrom<String> for TimeOfDay {
+    type Error = WallSwitchError;
+
+    fn try_from(value: String) -> WallSwitchResult<Self> {
+        value.parse()
+    }
+}
+
+impl From<TimeOfDay> for String {
+    fn from(time: TimeOfDay) -> Self {
+        time.to_string()
+    }
+}
+
+/// A time range overriding settings while it is active.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleRule {
+    /// Start of the range (inclusive).
+    pub from: TimeOfDay,
+    /// End of the range (exclusive).
+    pub to: TimeOfDay,
+    /// Named profile applied while the rule is active.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Settings overridden on top of the profile.
+    #[serde(flatten)]
+    pub settings: Profile,
+}
+
+impl ScheduleRule {
+    /// Returns true if the rule needs a `location` to compute sunrise or sunset.
+    pub fn uses_sun(&self) -> bool {
+        self.from.uses_sun() || self.to.uses_sun()
+    }
+
+    /// Start and end of the rule on the day of `now`, in seconds after local midnight.
+    pub fn bounds(&self, now: LocalTime, location: Option<&Location>) -> Option<(i64, i64)> {
+        let daylight = location.map(|location| location.daylight(now.timestamp));
+        Some((
+            self.from.resolve(now, daylight)?,
+            self.to.resolve(now, daylight)?,
+        ))
+    }
+
+    /// Returns true if `now` falls within the rule.
+    pub fn is_active(&self, now: LocalTime, location: Option<&Location>) -> bool {
+        let time = now.seconds_of_day();
+
+        match self.bounds(now, location) {
+            Some((from, to)) if from <= to => (from..to).contains(&time),
+            Some((from, to)) => time >= from || time < to,
+            None => false,
+        }
+    }
+}
+
+impl fmt::Display for ScheduleRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} - {}", self.from, self.to)?;
+        match &self.profile {
+            Some(profile) => write!(f, " ({profile})"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A moment as seen by the schedule: Unix time plus the local offset from UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalTime {
+    /// Seconds since the Unix epoch.
+    pub timestamp: i64,
+    /// Seconds to add to UTC to get the local clock time.
+    pub utc_offset: i64,
+}
+
+impl LocalTime {
+    /// Returns the current time with the offset of the system time zone.
+    pub fn now() -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+
+        LocalTime {
+            timestamp,
+            utc_offset: local_utc_offset(timestamp),
+        }
+    }
+
+    /// Seconds elapsed since local midnight.
+    pub fn seconds_of_day(&self) -> i64 {
+        (self.timestamp + self.utc_offset).rem_euclid(SECONDS_PER_DAY)
+    }
+}
+
+/// Returns the index of the first rule active at `now`.
+pub fn active_rule(
+    rules: &[ScheduleRule],
+    now: LocalTime,
+    location: Option<&Location>,
+) -> Option<usize> {
+    rules.iter().position(|rule| rule.is_active(now, location))
+}
+
+/// Time until the next rule starts or ends, `None` without rules.
+pub fn next_schedule_change(
+    rules: &[ScheduleRule],
+    now: LocalTime,
+    location: Option<&Location>,
+) -> Option<Duration> {
+    let time = now.seconds_of_day();
+
+    rules
+        .iter()
+        .filter_map(|rule| rule.bounds(now, location))
+        .flat_map(|(from, to)| [from, to])
+        .map(|bound| match (bound - time).rem_euclid(SECONDS_PER_DAY) {
+            0 => SECONDS_PER_DAY,
+            seconds => seconds,
+        })
+        .min()
+        .map(|seconds| Duration::from_secs(seconds as u64))
+}
+
+/// Returns the offset of the system time zone at `timestamp`.
+///
+/// The offset is read once per quarter hour: daylight saving transitions happen on
+/// whole or half local hours, so the cached value stays exact within the quarter.
+fn local_utc_offset(timestamp: i64) -> i64 {
+    static CACHE: Mutex<Option<(i64, i64)>> = Mutex::new(None);
+
+    let quarter = timestamp.div_euclid(SECONDS_PER_OFFSET);
+    let mut cache = CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+
+    match *cache {
+        Some((cached, offset)) if cached == quarter => offset,
+        _ => {
+            let offset = read_utc_offset();
+            *cache = Some((quarter, offset));
+            offset
+        }
+    }
+}
+
+/// Reads the offset of the system time zone from `date +%z` (e.g. `-0300`).
+///
+/// Falls back to UTC when `date` is unavailable.
+fn read_utc_offset() -> i64 {
+    Command::new("date")
+        .arg("+%z")
+        .output()
+        .ok()
+        .and_then(|output| parse_utc_offset(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or_default()
+}
+
+/// Parses a `±HHMM` offset into seconds.
+fn parse_utc_offset(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let (sign, digits) = match text.split_at_checked(1)? {
+        ("+", digits) => (1, digits),
+        ("-", digits) => (-1, digits),
+        _ => return None,
+    };
+
+    if digits.len() != 4 {
+        return None;
+    }
+    let hours: i64 = digits[..2].parse().ok()?;
+    let minutes: i64 = digits[2..].parse().ok()?;
+
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+//----------------------------------------------------------------------------//
+//                                   Tests                                    //
+//----------------------------------------------------------------------------//
+
+#[cfg(test)]
+mod tests_schedule {
+    use super::*;
+    use crate::ProceduralEffect;
+
+    /// 2024-06-21 at `hours`:`minutes` in Paris (UTC+2).
+    fn paris(hours: i64, minutes: i64) -> LocalTime {
+        LocalTime {
+            timestamp: 1_718_928_000 + hours * 3600 + minutes * 60 - 7200,
+            utc_offset: 7200,
+        }
+    }
+
+    #[test]
+    fn test_time_of_day_round_trip() {
+        for text in [
+            "07:30",
+            "00:00",
+            "24:00",
+            "sunrise",
+            "sunset-30",
+            "sunrise+45",
+        ] {
+            let time: TimeOfDay = text.parse().unwrap();
+            assert_eq!(time.to_string(), text);
+        }
+        for text in ["25:00", "7", "12:60", "noon", "sunset+x"] {
+            assert!(text.parse::<TimeOfDay>().is_err(), "{text}");
+        }
+        assert_eq!(parse_utc_offset("-0330\n"), Some(-12_600));
+        assert_eq!(parse_utc_offset("UTC"), None);
+    }
+
+    #[test]
+    fn test_active_rule() {
+        let rules: Vec<ScheduleRule> = serde_json::from_str(
+            r#"[
+                { "from": "sunset", "to": "sunrise", "profile": "night" },
+                { "from": "09:00", "to": "18:00", "effect": "none", "interval": 3600 }
+            ]"#,
+        )
+        .unwrap();
+        let location = Location {
+            latitude: 48.85,
+            longitude: 2.35,
+        };
+
+        assert_eq!(rules[1].settings.effect, Some(ProceduralEffect::None));
+        assert_eq!(rules[1].settings.interval, Some(3600));
+
+        // Paris: sunrise at 05:47 and sunset at 21:58 on the solstice
+        let active = |time| active_rule(&rules, time, Some(&location));
+        assert_eq!(active(paris(3, 0)), Some(0));
+        assert_eq!(active(paris(7, 0)), None);
+        assert_eq!(active(paris(12, 0)), Some(1));
+        assert_eq!(active(paris(19, 0)), None);
+        assert_eq!(active(paris(23, 30)), Some(0));
+
+        // The sun-based rule never matches without a location
+        assert_eq!(active_rule(&rules, paris(3, 0), None), None);
+
+        // 08:30 waits 30 minutes for the working hours
+        assert_eq!(
+            next_schedule_change(&rules, paris(8, 30), Some(&location)),
+            Some(Duration::from_secs(1800))
+        );
+        assert_eq!(next_schedule_change(&[], paris(8, 30), None), None);
+    }
+}
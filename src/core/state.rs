@@ -1,14 +1,16 @@
 use crate::{
-    AtomicWriteExt as _, Dimension, Environment, WallSwitchError, WallSwitchResult, get_config_path,
+    AtomicWriteExt as _, CycleRecord, Dimension, Environment, FileInfo, FocalPoint,
+    WallSwitchError, WallSwitchResult, get_config_path,
 };
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs,
     io::{BufWriter, Write},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
+/// Maximum number of image paths kept in the history (and in `skipped`).
 const MAX_ITENS: usize = 10_000;
 
 /// Represents cached metadata of an image file to prevent redundant hashing and dimension probing.
@@ -19,37 +21,84 @@ pub struct CacheEntry {
     pub hash: String,
     #[serde(default)]
     pub dimension: Option<Dimension>,
+    /// Point of interest (source pixels) kept in view when the image is cropped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub focal_point: Option<FocalPoint>,
 }
 
 /// Manages the persistence of the wallpaper history loop and the smart file cache.
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct State {
-    pub history: Vec<PathBuf>,
+    /// Applied wallpaper sets, oldest first.
+    #[serde(default)]
+    pub history: Vec<CycleRecord>,
+    /// Index in `history` where the current rotation starts.
+    ///
+    /// Images of older cycles may be selected again.
+    #[serde(default)]
+    pub rotation_start: usize,
+    /// Paths excluded until the next rotation that do not belong to any cycle
+    /// (corrupt files, or history migrated from the old flat format).
+    #[serde(default)]
+    pub skipped: Vec<PathBuf>,
+    /// Number of cycles run so far; unlike the truncated `history`, it never stops growing.
+    #[serde(default)]
+    pub cycles: u64,
     pub hashes: HashMap<PathBuf, CacheEntry>,
 }
 
+/// State file layout used before history was grouped per cycle.
+#[derive(Deserialize)]
+struct LegacyState {
+    history: Vec<PathBuf>,
+    #[serde(default)]
+    cycles: Vec<CycleRecord>,
+    hashes: HashMap<PathBuf, CacheEntry>,
+}
+
+impl From<LegacyState> for State {
+    /// The old flat path list carries no cycle information, so it becomes the
+    /// exclusion list of the current rotation; recorded cycles (if any) are kept
+    /// for navigation only.
+    fn from(legacy: LegacyState) -> Self {
+        State {
+            rotation_start: legacy.cycles.len(),
+            cycles: legacy.cycles.len() as u64,
+            history: legacy.cycles,
+            skipped: legacy.history,
+            hashes: legacy.hashes,
+        }
+    }
+}
+
 impl State {
     /// Loads the persistent state file from the system configuration path.
+    ///
+    /// Files written by older versions (flat `history` path list) are migrated.
     pub fn load(env: &Environment) -> Self {
         if let Ok(path) = Self::get_path(env)
             && let Ok(content) = fs::read_to_string(&path)
-            && let Ok(state) = serde_json::from_str(&content)
+            && let Some(state) = Self::parse(&content)
         {
             return state;
         }
         State::default()
     }
 
+    /// Parses the current state format, falling back to the legacy layout.
+    fn parse(content: &str) -> Option<Self> {
+        serde_json::from_str(content)
+            .or_else(|_| serde_json::from_str::<LegacyState>(content).map(State::from))
+            .ok()
+    }
+
     /// Atomically persists the history loops and image metadata cache to disk.
     ///
     /// # Errors
     ///
     /// Returns a [`WallSwitchResult`] if writing the file fails.
     pub fn save(&mut self, env: &Environment) -> WallSwitchResult<()> {
-        if self.history.len() > MAX_ITENS {
-            let start = self.history.len() - MAX_ITENS;
-            self.history = self.history[start..].to_vec();
-        }
+        self.truncate();
 
         let path = Self::get_path(env)?;
 
@@ -68,6 +117,135 @@ impl State {
         Ok(())
     }
 
+    /// Drops the oldest cycles until the history holds at most `MAX_ITENS` image paths.
+    ///
+    /// Each cycle counts at least once, so procedural cycles without images are capped too.
+    fn truncate(&mut self) {
+        let mut paths: usize = self.history.iter().map(path_count).sum();
+        let mut start = 0;
+
+        while paths > MAX_ITENS {
+            paths -= path_count(&self.history[start]);
+            start += 1;
+        }
+
+        self.history.drain(..start);
+        self.rotation_start = self.rotation_start.saturating_sub(start);
+
+        if self.skipped.len() > MAX_ITENS {
+            let start = self.skipped.len() - MAX_ITENS;
+            self.skipped.drain(..start);
+        }
+    }
+
+    /// Returns the number of the cycle about to run and counts it.
+    pub fn next_cycle(&mut self) -> u64 {
+        let cycle = self.cycles;
+        self.cycles = self.cycles.wrapping_add(1);
+        cycle
+    }
+
+    /// Returns the set applied `back` cycles ago (`0` is the current one).
+    pub fn cycle(&self, back: usize) -> Option<&CycleRecord> {
+        self.history.iter().rev().nth(back)
+    }
+
+    /// Returns every path that must not be selected again in the current rotation.
+    pub fn seen_paths(&self) -> HashSet<&Path> {
+        self.history
+            .get(self.rotation_start..)
+            .unwrap_or_default()
+            .iter()
+            .flat_map(|record| &record.monitors)
+            .flat_map(|monitor| &monitor.images)
+            .chain(&self.skipped)
+            .map(PathBuf::as_path)
+            .collect()
+    }
+
+    /// Keeps the `files` not shown yet in the current rotation, rotating each group on its own.
+    ///
+    /// `group` names the group of a path (e.g. its weighted collection). Once every
+    /// file of a group has been shown, that group starts over while the others go on,
+    /// so a small group comes back as often as it is drawn. Skipped paths stay excluded.
+    pub fn unseen<K: PartialEq>(
+        &self,
+        files: Vec<FileInfo>,
+        group: impl Fn(&Path) -> K,
+    ) -> Vec<FileInfo> {
+        let skipped: HashSet<&Path> = self.skipped.iter().map(PathBuf::as_path).collect();
+
+        // Group index of every selectable file, and the number of files per group
+        let mut keys: Vec<K> = Vec::new();
+        let mut sizes: Vec<usize> = Vec::new();
+        let mut owner: HashMap<&Path, usize> = HashMap::new();
+
+        for file in &files {
+            let path = file.path.as_path();
+            if skipped.contains(path) {
+                continue;
+            }
+            let key = group(path);
+            let index = keys
+                .iter()
+                .position(|other| *other == key)
+                .unwrap_or_else(|| {
+                    keys.push(key);
+                    sizes.push(0);
+                    keys.len() - 1
+                });
+            sizes[index] += 1;
+            owner.insert(path, index);
+        }
+
+        // Replay the rotation, emptying a group's seen set whenever the group is complete
+        let mut seen: Vec<HashSet<&Path>> = vec![HashSet::new(); keys.len()];
+        let shown = self
+            .history
+            .get(self.rotation_start..)
+            .unwrap_or_default()
+            .iter()
+            .flat_map(|record| &record.monitors)
+            .flat_map(|monitor| &monitor.images);
+
+        for path in shown {
+            if let Some(&index) = owner.get(path.as_path()) {
+                seen[index].insert(path);
+                if seen[index].len() == sizes[index] {
+                    seen[index].clear();
+                }
+            }
+        }
+
+        let keep: Vec<bool> = files
+            .iter()
+            .map(|file| {
+                owner
+                    .get(file.path.as_path())
+                    .is_some_and(|&index| !seen[index].contains(file.path.as_path()))
+            })
+            .collect();
+
+        files
+            .into_iter()
+            .zip(keep)
+            .filter_map(|(file, keep)| keep.then_some(file))
+            .collect()
+    }
+
+    /// Returns true if the current rotation has excluded any path yet.
+    pub fn has_seen(&self) -> bool {
+        self.rotation_start < self.history.len() || !self.skipped.is_empty()
+    }
+
+    /// Starts a new rotation: every image becomes selectable again.
+    ///
+    /// Recorded cycles are kept for `--history` and `--previous`.
+    pub fn reset_rotation(&mut self) {
+        self.rotation_start = self.history.len();
+        self.skipped.clear();
+    }
+
     /// Removes untracked paths that no longer exist on the current filesystem from the cache.
     pub fn garbage_collect(&mut self) {
         self.hashes.retain(|path, _| path.exists());
@@ -81,3 +259,114 @@ impl State {
         Ok(path)
     }
 }
+
+/// Number of image paths of a cycle, at least one.
+fn path_count(record: &CycleRecord) -> usize {
+    record
+        .monitors
+        .iter()
+        .map(|monitor| monitor.images.len())
+        .sum::<usize>()
+        .max(1)
+}
+
+//----------------------------------------------------------------------------//
+//                                   Tests                                    //
+//----------------------------------------------------------------------------//
+
+#[cfg(test)]
+mod tests_state {
+    use super::*;
+    use crate::{CanvasBase, MonitorRecord, ProceduralEffect};
+
+    fn record(paths: &[&str]) -> CycleRecord {
+        CycleRecord::new(vec![MonitorRecord {
+            monitor: 0,
+            output: None,
+            images: paths.iter().map(PathBuf::from).collect(),
+            base: CanvasBase::Photo,
+            effect: ProceduralEffect::None,
+            preset: None,
+        }])
+    }
+
+    #[test]
+    fn test_legacy_state_migration() {
+        let content = r#"{
+            "history": ["/a.png", "/b.png"],
+            "hashes": {
+                "/a.png": { "size": 1, "mtime": 2, "hash": "h" }
+            }
+        }"#;
+
+        let state = State::parse(content).expect("legacy state must load");
+        assert!(state.history.is_empty());
+        assert_eq!(state.skipped, ["/a.png", "/b.png"].map(PathBuf::from));
+        assert_eq!(state.hashes.len(), 1);
+        assert!(state.seen_paths().contains(Path::new("/b.png")));
+    }
+
+    #[test]
+    fn test_rotation() {
+        let mut state = State::default();
+        assert!(!state.has_seen());
+
+        state.history.push(record(&["/a.png"]));
+        state.history.push(record(&["/b.png", "/c.png"]));
+        state.skipped.push(PathBuf::from("/corrupt.png"));
+        assert_eq!(state.seen_paths().len(), 4);
+
+        state.reset_rotation();
+        assert!(!state.has_seen());
+        assert!(state.seen_paths().is_empty());
+        assert_eq!(state.history.len(), 2);
+
+        state.history.push(record(&["/a.png"]));
+        assert_eq!(state.seen_paths().len(), 1);
+        assert_eq!(
+            state.cycle(0).unwrap().monitors[0].images[0],
+            Path::new("/a.png")
+        );
+        assert_eq!(
+            state.cycle(2).unwrap().monitors[0].images[0],
+            Path::new("/a.png")
+        );
+        assert!(state.cycle(3).is_none());
+    }
+
+    #[test]
+    fn test_truncate_by_path_count() {
+        let mut state = State::default();
+        let paths: Vec<String> = (0..4).map(|i| format!("/{i}.png")).collect();
+        let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+
+        // Four paths per cycle: the cap keeps a quarter as many cycles
+        for _ in 0..MAX_ITENS / 2 {
+            state.history.push(record(&paths));
+        }
+        state.rotation_start = 10;
+        state.truncate();
+
+        assert_eq!(state.history.len(), MAX_ITENS / 4);
+        assert_eq!(state.rotation_start, 0);
+    }
+
+    #[test]
+    fn test_cycle_counter_outlives_truncation() {
+        let mut state = State::default();
+        let paths: Vec<String> = (0..4).map(|i| format!("/{i}.png")).collect();
+        let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+
+        // Past the cap the history length stalls, while every cycle keeps its own number
+        let mut numbers = HashSet::new();
+        for _ in 0..MAX_ITENS / 2 {
+            numbers.insert(state.next_cycle());
+            state.history.push(record(&paths));
+            state.truncate();
+        }
+
+        assert_eq!(state.history.len(), MAX_ITENS / 4);
+        assert_eq!(numbers.len(), MAX_ITENS / 2);
+        assert_eq!(state.cycles, (MAX_ITENS / 2) as u64);
+    }
+}
@@ -0,0 +1,200 @@
+//! Offline sunrise, sunset and sun position for a configured location.
+//!
+//! Uses the sunrise equation (solar mean anomaly, equation of the centre and
+//! ecliptic longitude), which is accurate to about a minute between the polar
+//! circles: plenty for switching wallpapers.
+
+use crate::{WallSwitchError, WallSwitchResult};
+use serde::{Deserialize, Serialize};
+
+/// Julian date of the Unix epoch (1970-01-01 00:00 UTC).
+const JULIAN_UNIX_EPOCH: f64 = 2_440_587.5;
+
+/// Julian date of the J2000 epoch (2000-01-01 12:00 TT).
+const JULIAN_2000: f64 = 2_451_545.0;
+
+/// Obliquity of the ecliptic, in degrees.
+const OBLIQUITY: f64 = 23.44;
+
+/// Solar elevation at sunrise and sunset: refraction plus the radius of the disc.
+const HORIZON: f64 = -0.833;
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Geographic position used for sun-based schedules.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    /// Degrees north of the equator (negative in the southern hemisphere).
+    pub latitude: f64,
+    /// Degrees east of Greenwich (negative in the Americas).
+    pub longitude: f64,
+}
+
+/// Position of the sun in the sky, in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SunPosition {
+    /// Angle above the horizon (negative at night).
+    pub elevation: f64,
+    /// Compass direction, clockwise from north (90 is east).
+    pub azimuth: f64,
+}
+
+/// When the sun is up during one day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Daylight {
+    /// The sun rises and sets (Unix timestamps, in seconds).
+    Day { sunrise: i64, sunset: i64 },
+    /// The sun never sets (polar day).
+    AlwaysUp,
+    /// The sun never rises (polar night).
+    AlwaysDown,
+}
+
+impl Location {
+    /// Checks that the coordinates are on the globe.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WallSwitchError::InvalidValue`] for a latitude outside ±90
+    /// or a longitude outside ±180 degrees.
+    pub fn validate(&self) -> WallSwitchResult<()> {
+        for (arg, value, limit) in [
+            ("latitude", self.latitude, 90.0),
+            ("longitude", self.longitude, 180.0),
+        ] {
+            if !(-limit..=limit).contains(&value) {
+                return Err(WallSwitchError::InvalidValue {
+                    arg: arg.to_string(),
+                    value: value.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the sunrise and sunset of the solar day around `timestamp`.
+    pub fn daylight(&self, timestamp: i64) -> Daylight {
+        let (transit, declination) = self.solar_day(timestamp);
+
+        let latitude = self.latitude.to_radians();
+        let cos_hour_angle = (HORIZON.to_radians().sin() - latitude.sin() * declination.sin())
+            / (latitude.cos() * declination.cos());
+
+        if cos_hour_angle < -1.0 {
+            return Daylight::AlwaysUp;
+        }
+        if cos_hour_angle > 1.0 {
+            return Daylight::AlwaysDown;
+        }
+
+        let half_day = cos_hour_angle.acos().to_degrees() / 360.0;
+        let to_unix = |julian: f64| ((julian - JULIAN_UNIX_EPOCH) * SECONDS_PER_DAY).round() as i64;
+
+        Daylight::Day {
+            sunrise: to_unix(transit - half_day),
+            sunset: to_unix(transit + half_day),
+        }
+    }
+
+    /// Returns the position of the sun at `timestamp`.
+    pub fn sun_position(&self, timestamp: i64) -> SunPosition {
+        let (transit, declination) = self.solar_day(timestamp);
+        let julian = timestamp as f64 / SECONDS_PER_DAY + JULIAN_UNIX_EPOCH;
+
+        // The sun moves 360 degrees westwards per day from its transit
+        let hour_angle = (julian - transit) * std::f64::consts::TAU;
+        let latitude = self.latitude.to_radians();
+
+        let elevation = (latitude.sin() * declination.sin()
+            + latitude.cos() * declination.cos() * hour_angle.cos())
+        .asin();
+        let azimuth = hour_angle
+            .sin()
+            .atan2(hour_angle.cos() * latitude.sin() - declination.tan() * latitude.cos());
+
+        SunPosition {
+            elevation: elevation.to_degrees(),
+            azimuth: (azimuth.to_degrees() + 180.0).rem_euclid(360.0),
+        }
+    }
+
+    /// Returns the solar transit (Julian date) and the declination of the sun (radians)
+    /// of the solar day around `timestamp`.
+    fn solar_day(&self, timestamp: i64) -> (f64, f64) {
+        let julian = timestamp as f64 / SECONDS_PER_DAY + JULIAN_UNIX_EPOCH;
+
+        // Mean solar noon, in days since J2000
+        let day = (julian - JULIAN_2000 + self.longitude / 360.0).round();
+        let noon = day - self.longitude / 360.0;
+
+        let anomaly = (357.5291 + 0.985_600_28 * noon)
+            .rem_euclid(360.0)
+            .to_radians();
+        let centre = 1.9148 * anomaly.sin()
+            + 0.0200 * (2.0 * anomaly).sin()
+            + 0.0003 * (3.0 * anomaly).sin();
+        let ecliptic = (anomaly.to_degrees() + centre + 180.0 + 102.9372)
+            .rem_euclid(360.0)
+            .to_radians();
+
+        let transit = JULIAN_2000 + noon + 0.0053 * anomaly.sin() - 0.0069 * (2.0 * ecliptic).sin();
+        let declination = (ecliptic.sin() * OBLIQUITY.to_radians().sin()).asin();
+
+        (transit, declination)
+    }
+}
+
+//----------------------------------------------------------------------------//
+//                                   Tests                                    //
+//----------------------------------------------------------------------------//
+
+#[cfg(test)]
+mod tests_sun {
+    use super::*;
+
+    #[test]
+    fn test_daylight() {
+        // 2024-06-21 12:00 UTC
+        let solstice = 1_718_971_200;
+
+        // London: sunrise at 04:43 and sunset at 21:21 BST (03:43 and 20:21 UTC)
+        let london = Location {
+            latitude: 51.5074,
+            longitude: -0.1278,
+        };
+        let Daylight::Day { sunrise, sunset } = london.daylight(solstice) else {
+            panic!("the sun rises in London");
+        };
+        let utc_minutes = |timestamp: i64| timestamp.rem_euclid(86_400) / 60;
+        assert!((utc_minutes(sunrise) - (3 * 60 + 43)).abs() <= 3);
+        assert!((utc_minutes(sunset) - (20 * 60 + 21)).abs() <= 3);
+
+        // At sunrise the sun is on the horizon in the north-east, at noon high in the south
+        let dawn = london.sun_position(sunrise);
+        assert!(dawn.elevation.abs() < 1.0, "{dawn:?}");
+        assert!((dawn.azimuth - 49.0).abs() < 2.0, "{dawn:?}");
+        let noon = london.sun_position((sunrise + sunset) / 2);
+        assert!((noon.elevation - 61.9).abs() < 0.5, "{noon:?}");
+        assert!((noon.azimuth - 180.0).abs() < 1.0, "{noon:?}");
+
+        // Tromsø has midnight sun in June and polar night in December
+        let tromso = Location {
+            latitude: 69.6492,
+            longitude: 18.9553,
+        };
+        assert_eq!(tromso.daylight(solstice), Daylight::AlwaysUp);
+        assert_eq!(
+            tromso.daylight(solstice + 183 * 86_400),
+            Daylight::AlwaysDown
+        );
+
+        assert!(
+            Location {
+                latitude: 91.0,
+                longitude: 0.0
+            }
+            .validate()
+            .is_err()
+        );
+    }
+}
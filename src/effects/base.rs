@@ -0,0 +1,157 @@
+//! Generated canvas bases for pure procedural wallpapers.
+//!
+//! Overlays are normally blended onto the source photos assembled for a monitor.
+//! A generated base replaces those photos with a canvas drawn from `NEON_PALETTES`,
+//! so any overlay (or the base alone) can stand as the whole wallpaper.
+//!
+//! Colour picks use the active random stream, so a seeded cycle redraws the
+//! exact same base.
+
+use crate::{ColorRGB, NEON_PALETTES, RandomExt, WallSwitchResult, process_rows_parallel_scoped};
+use clap::ValueEnum;
+use image::RgbImage;
+use serde::{Deserialize, Serialize};
+
+/// Brightness of palette colours used as a full-screen fill (keeps overlays readable).
+const FILL_BRIGHTNESS: f64 = 0.35;
+
+/// Starting point of a monitor canvas, before any overlay is drawn.
+#[derive(
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum CanvasBase {
+    /// Source photos from the image directories.
+    #[value(name = "photo")]
+    #[default]
+    Photo,
+
+    /// A single deep colour picked from the neon palettes.
+    #[value(name = "solid")]
+    Solid,
+
+    /// Diagonal gradient between two distinct neon palette colours.
+    #[value(name = "gradient")]
+    Gradient,
+
+    /// Near-black field with a faint palette tint and a soft vignette.
+    #[value(name = "dark")]
+    Dark,
+}
+
+impl CanvasBase {
+    /// Human-readable display name for diagnostics and terminal output.
+    pub fn get_name(self) -> &'static str {
+        match self {
+            Self::Photo => "Photo",
+            Self::Solid => "Solid",
+            Self::Gradient => "Gradient",
+            Self::Dark => "Dark",
+        }
+    }
+
+    /// Returns true if the canvas is drawn instead of assembled from photos.
+    pub fn is_generated(self) -> bool {
+        self != Self::Photo
+    }
+
+    /// Draws a `width` x `height` base canvas.
+    ///
+    /// [`CanvasBase::Photo`] has nothing to draw and yields a black canvas.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`WallSwitchResult`] if the palette table is empty.
+    pub fn generate(self, width: u32, height: u32) -> WallSwitchResult<RgbImage> {
+        let mut canvas = RgbImage::new(width, height);
+
+        let inv_w = 1.0 / width.max(1) as f64;
+        let inv_h = 1.0 / height.max(1) as f64;
+
+        match self {
+            Self::Photo => {}
+            Self::Solid => {
+                let color = NEON_PALETTES.get_random_sample()?.color_rgb;
+                let fill = color.scale(FILL_BRIGHTNESS);
+
+                process_rows_parallel_scoped(&mut canvas, |_y, row_data| {
+                    for pixel_slice in row_data.chunks_exact_mut(3) {
+                        fill.write_to_slice(pixel_slice);
+                    }
+                });
+            }
+            Self::Gradient => {
+                let mut pair = NEON_PALETTES.to_vec();
+                pair.shuffle();
+                let start = pair[0].color_rgb.scale(FILL_BRIGHTNESS);
+                let end = pair[pair.len().min(2) - 1].color_rgb.scale(FILL_BRIGHTNESS);
+
+                process_rows_parallel_scoped(&mut canvas, |y, row_data| {
+                    let v = y as f64 * inv_h;
+                    for (x, pixel_slice) in row_data.chunks_exact_mut(3).enumerate() {
+                        let t = 0.5 * (x as f64 * inv_w + v);
+                        start.lerp(end, t).write_to_slice(pixel_slice);
+                    }
+                });
+            }
+            Self::Dark => {
+                let tint = NEON_PALETTES.get_random_sample()?.color_rgb;
+                let floor = ColorRGB::splat(0.015);
+
+                process_rows_parallel_scoped(&mut canvas, |y, row_data| {
+                    let dy = y as f64 * inv_h - 0.5;
+                    for (x, pixel_slice) in row_data.chunks_exact_mut(3).enumerate() {
+                        let dx = x as f64 * inv_w - 0.5;
+                        // Vignette: 1.0 at the centre, fading towards the corners
+                        let glow = (1.0 - 2.0 * (dx * dx + dy * dy)).max(0.0);
+                        (floor + tint * (0.07 * glow)).write_to_slice(pixel_slice);
+                    }
+                });
+            }
+        }
+
+        Ok(canvas)
+    }
+}
+
+//----------------------------------------------------------------------------//
+//                                   Tests                                    //
+//----------------------------------------------------------------------------//
+
+#[cfg(test)]
+mod tests_base {
+    use super::*;
+    use crate::with_seed;
+
+    #[test]
+    fn test_generated_bases() -> WallSwitchResult<()> {
+        for base in [CanvasBase::Solid, CanvasBase::Gradient, CanvasBase::Dark] {
+            let canvas = with_seed(3, || base.generate(48, 27))?;
+            assert_eq!(canvas.dimensions(), (48, 27));
+            assert_eq!(canvas, with_seed(3, || base.generate(48, 27))?);
+            assert!(
+                canvas.pixels().any(|p| p.0 != [0, 0, 0]),
+                "{base:?} is black"
+            );
+        }
+
+        let dark = CanvasBase::Dark.generate(64, 64)?;
+        let brightness = |x, y| dark.get_pixel(x, y).0.map(u32::from).iter().sum::<u32>();
+        let (corner, centre) = (brightness(0, 0), brightness(32, 32));
+        assert!(centre >= corner, "vignette must brighten the centre");
+
+        assert!(!CanvasBase::Photo.is_generated());
+        Ok(())
+    }
+}
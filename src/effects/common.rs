@@ -12,14 +12,14 @@ use crate::effects::{
     newton::NewtonGenerator, nova::NovaGenerator, star::StarfieldGenerator,
 };
 use crate::{
-    ColorRGB, Complex, Config, Monitor, NeonColor, WallSwitchError, WallSwitchResult,
-    get_random_integer,
+    ColorRGB, Complex, Config, EffectsConfig, Monitor, NeonColor, WallSwitchError,
+    WallSwitchResult, get_random_integer, get_weighted_index, percent_chance,
 };
 use clap::ValueEnum;
 use image::RgbImage;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::f64::consts::FRAC_1_SQRT_2;
+use std::{collections::BTreeMap, f64::consts::FRAC_1_SQRT_2};
 use std::{
     f64::consts::{LOG2_E, PI},
     io::Error,
@@ -44,6 +44,11 @@ pub trait ImageEffect: Sync + Send {
     /// Returns a formatted string containing diagnostic details of the active effect.
     fn info(&self) -> String;
 
+    /// Returns the name of the preset picked by the generator, if it uses presets.
+    fn preset_name(&self) -> Option<String> {
+        None
+    }
+
     /// Convenience helper: opens `input_path`, runs the effect, writes `output_path`.
     fn apply_effect(&self, input_path: &Path, output_path: &Path) -> WallSwitchResult<()> {
         let img = image::open(input_path)
@@ -106,6 +111,9 @@ pub trait FractalDescriptor {
 
     /// Returns a comprehensive diagnostic string formatted for the generator's equation.
     fn info_text(&self) -> String;
+
+    /// Human-readable name of the selected coordinate preset.
+    fn preset_name(&self) -> &str;
 }
 
 /// Blanket implementation: any type that implements [`FractalDescriptor`] automatically
@@ -126,6 +134,10 @@ impl<T: FractalDescriptor + Sync + Send> ImageEffect for T {
     fn info(&self) -> String {
         self.info_text()
     }
+
+    fn preset_name(&self) -> Option<String> {
+        Some(FractalDescriptor::preset_name(self).to_string())
+    }
 }
 
 // ============================================================================
@@ -133,7 +145,20 @@ impl<T: FractalDescriptor + Sync + Send> ImageEffect for T {
 // ============================================================================
 
 /// Represents all supported procedural background overlay effects.
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[derive(
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ValueEnum,
+)]
 #[serde(rename_all = "lowercase")]
 pub enum ProceduralEffect {
     /// No overlay effect is applied; displays the raw, unaltered wallpaper.
@@ -220,6 +245,16 @@ pub enum ProceduralEffect {
 }
 
 impl ProceduralEffect {
+    /// Every effect that draws something, in the order `random` picks from.
+    pub const CONCRETE: [Self; 6] = [
+        Self::JuliaSet,
+        Self::Mandelbrot,
+        Self::NewtonBasins,
+        Self::NovaJulia,
+        Self::CosmicAurora,
+        Self::Starfield,
+    ];
+
     /// Human-readable display name for diagnostics and terminal output.
     pub fn get_name(self) -> &'static str {
         match self {
@@ -236,35 +271,56 @@ impl ProceduralEffect {
         }
     }
 
-    /// Resolves meta-variants (`Fractal`, `Random`) to a single concrete effect.
+    /// Returns the concrete effects this variant may resolve to.
     ///
-    /// Concrete variants pass through unchanged so callers can always call
-    /// `resolve()` unconditionally.
-    pub fn resolve(self) -> Self {
+    /// Concrete variants return themselves and `None` returns an empty slice.
+    pub fn candidates(self) -> &'static [Self] {
         match self {
-            Self::Random => match get_random_integer(0, 5) {
-                // Fractal
-                0 => Self::JuliaSet,
-                1 => Self::Mandelbrot,
-                // Polynomial
-                2 => Self::NewtonBasins,
-                3 => Self::NovaJulia,
-                // Others
-                4 => Self::CosmicAurora,
-                _ => Self::Starfield,
-            },
-            Self::Fractal => match get_random_integer(0, 1) {
-                0 => Self::JuliaSet,
-                _ => Self::Mandelbrot,
-            },
-            Self::Polynomial => match get_random_integer(0, 1) {
-                0 => Self::NewtonBasins,
-                _ => Self::NovaJulia,
-            },
-            concrete => concrete,
+            Self::None => &[],
+            Self::JuliaSet => &[Self::JuliaSet],
+            Self::Mandelbrot => &[Self::Mandelbrot],
+            Self::NewtonBasins => &[Self::NewtonBasins],
+            Self::NovaJulia => &[Self::NovaJulia],
+            Self::CosmicAurora => &[Self::CosmicAurora],
+            Self::Starfield => &[Self::Starfield],
+            Self::Fractal => &[Self::JuliaSet, Self::Mandelbrot],
+            Self::Polynomial => &[Self::NewtonBasins, Self::NovaJulia],
+            Self::Random => &Self::CONCRETE,
         }
     }
 
+    /// Resolves the effect drawn in one cycle.
+    ///
+    /// An overlay is drawn with a chance of `effects.percent`, then meta-variants
+    /// are picked according to `effects.weights` (see [`pick`](Self::pick)).
+    pub fn resolve(self, effects: &EffectsConfig) -> Self {
+        if self == Self::None || !percent_chance(effects.percent) {
+            return Self::None;
+        }
+
+        self.pick(&effects.weights)
+    }
+
+    /// Resolves meta-variants (`Fractal`, `Polynomial`, `Random`) to a single concrete effect.
+    ///
+    /// Each candidate is picked with a probability proportional to its weight (missing
+    /// entries weigh 1); if every candidate weighs 0 the result is `None`. Concrete
+    /// variants pass through unchanged, whatever their weight.
+    pub fn pick(self, weights: &BTreeMap<Self, u32>) -> Self {
+        let candidates = self.candidates();
+
+        if let [single] = candidates {
+            return *single;
+        }
+
+        let weights: Vec<u64> = candidates
+            .iter()
+            .map(|effect| weights.get(effect).copied().unwrap_or(1).into())
+            .collect();
+
+        get_weighted_index(&weights).map_or(Self::None, |index| candidates[index])
+    }
+
     /// Constructs a heap-allocated, monitor-fitted [`ImageEffect`] for this variant.
     ///
     /// Returns `None` for [`ProceduralEffect::None`] and the meta-variants
@@ -990,20 +1046,63 @@ mod tests_common {
 
     #[test]
     fn test_procedural_effect_resolution() {
-        let resolved_rand = ProceduralEffect::Random.resolve();
+        let effects = EffectsConfig::default();
+
+        let resolved_rand = ProceduralEffect::Random.resolve(&effects);
         assert_ne!(resolved_rand, ProceduralEffect::Random);
         assert_ne!(resolved_rand, ProceduralEffect::Fractal);
 
-        let resolved_fractal = ProceduralEffect::Fractal.resolve();
+        let resolved_fractal = ProceduralEffect::Fractal.resolve(&effects);
         assert_ne!(resolved_fractal, ProceduralEffect::Fractal);
         assert_ne!(resolved_fractal, ProceduralEffect::Random);
 
         // Concrete variants are identity under resolve().
         assert_eq!(
-            ProceduralEffect::JuliaSet.resolve(),
+            ProceduralEffect::JuliaSet.resolve(&effects),
             ProceduralEffect::JuliaSet
         );
-        assert_eq!(ProceduralEffect::None.resolve(), ProceduralEffect::None);
+        assert_eq!(
+            ProceduralEffect::None.resolve(&effects),
+            ProceduralEffect::None
+        );
+    }
+
+    #[test]
+    fn test_weighted_effect_resolution() {
+        let mut effects = EffectsConfig {
+            weights: BTreeMap::from([
+                (ProceduralEffect::NovaJulia, 5),
+                (ProceduralEffect::CosmicAurora, 0),
+                (ProceduralEffect::JuliaSet, 0),
+                (ProceduralEffect::Mandelbrot, 0),
+            ]),
+            ..EffectsConfig::default()
+        };
+
+        // Zero weights are never picked, but explicit choices ignore weights
+        for _ in 0..200 {
+            let picked = ProceduralEffect::Random.pick(&effects.weights);
+            assert!(!matches!(
+                picked,
+                ProceduralEffect::CosmicAurora
+                    | ProceduralEffect::JuliaSet
+                    | ProceduralEffect::Mandelbrot
+            ));
+        }
+        assert_eq!(
+            ProceduralEffect::Fractal.pick(&effects.weights),
+            ProceduralEffect::None
+        );
+        assert_eq!(
+            ProceduralEffect::CosmicAurora.pick(&effects.weights),
+            ProceduralEffect::CosmicAurora
+        );
+
+        effects.percent = 0;
+        assert_eq!(
+            ProceduralEffect::NovaJulia.resolve(&effects),
+            ProceduralEffect::None
+        );
     }
 
     #[test]
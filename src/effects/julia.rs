@@ -292,6 +292,10 @@ impl FractalDescriptor for JuliaGenerator {
         )
     }
 
+    fn preset_name(&self) -> &str {
+        &self.preset.fractal_name
+    }
+
     fn info_text(&self) -> String {
         format!(
             "fractal [{}]\n\
@@ -399,6 +403,7 @@ impl JuliaGenerator {
 #[cfg(test)]
 mod tests_julia {
     use super::*;
+    use crate::with_seed;
 
     #[test]
     fn test_random_generator_sanity() -> WallSwitchResult<()> {
@@ -418,6 +423,24 @@ mod tests_julia {
         Ok(())
     }
 
+    #[test]
+    fn test_seeded_generator_is_reproducible() -> WallSwitchResult<()> {
+        let config = Config::default();
+        let build = |seed| {
+            with_seed(seed, || JuliaGenerator::new(&config)).map(|julia| {
+                let rotation = julia.config.rotation;
+                (
+                    julia.info_text(),
+                    rotation.re.to_bits(),
+                    rotation.im.to_bits(),
+                )
+            })
+        };
+
+        assert_eq!(build(2024)?, build(2024)?);
+        Ok(())
+    }
+
     #[test]
     fn test_all_presets_have_correct_effect_name() {
         for preset in JULIA_PRESETS {
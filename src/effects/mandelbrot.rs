@@ -130,6 +130,10 @@ impl FractalDescriptor for MandelbrotGenerator {
         )
     }
 
+    fn preset_name(&self) -> &str {
+        &self.preset.fractal_name
+    }
+
     fn info_text(&self) -> String {
         format!(
             "fractal [{}]\n\
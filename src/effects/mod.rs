@@ -1,4 +1,5 @@
 mod aurora;
+mod base;
 mod common;
 mod julia;
 mod mandelbrot;
@@ -6,4 +7,6 @@ mod newton;
 mod nova;
 mod star;
 
-pub use self::{aurora::*, common::*, julia::*, mandelbrot::*, newton::*, nova::*, star::*};
+pub use self::{
+    aurora::*, base::*, common::*, julia::*, mandelbrot::*, newton::*, nova::*, star::*,
+};
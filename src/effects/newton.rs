@@ -126,6 +126,10 @@ impl FractalDescriptor for NewtonGenerator {
         .color_newton(self.config.color_palette, edge_fade, (1e-6_f64).ln())
     }
 
+    fn preset_name(&self) -> &str {
+        &self.preset.name
+    }
+
     fn info_text(&self) -> String {
         format!(
             "fractal [{}]\n\
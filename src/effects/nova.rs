@@ -128,6 +128,10 @@ impl FractalDescriptor for NovaGenerator {
         .color_nova(self.config.color_palette, edge_fade, (1e-5_f64).ln())
     }
 
+    fn preset_name(&self) -> &str {
+        &self.preset.name
+    }
+
     fn info_text(&self) -> String {
         format!(
             "fractal [{}]\n\
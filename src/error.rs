@@ -83,6 +83,10 @@ pub enum WallSwitchError {
     #[error("{0} daemon failed to start or is unresponsive: {1}")]
     DaemonError(String, String),
 
+    /// Error reported by the running daemon in reply to a control command.
+    #[error("{e}: daemon rejected the command: {0}", e = "Error".red().bold())]
+    DaemonReply(String),
+
     /// Error when desktop environment detection fails completely.
     #[error("{e}: Could not detect desktop environment. Please set DESKTOP_SESSION manually.", e = "Error".red().bold())]
     DesktopDetectionFailed,
@@ -103,6 +107,15 @@ pub enum WallSwitchError {
     #[error("Failed to convert command output to UTF-8: {0}")]
     FromUtf8(#[from] FromUtf8Error),
 
+    /// Error when the requested history entry does not exist.
+    #[error(
+        "{e}: cannot go back {b} cycle(s): only {a} wallpaper set(s) recorded in history.",
+        e = "Error".red().bold(),
+        b = back.to_string().yellow(),
+        a = available.to_string().green(),
+    )]
+    HistoryUnavailable { back: usize, available: usize },
+
     /// Error propagated from the image processing library.
     #[error("Image library error: {0}")]
     Image(#[from] ImageError),
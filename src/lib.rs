@@ -15,15 +15,30 @@ src/
 │   ├── list.rs           # Formatted table/JSON display and sorting of image metadata.
 │   └── mod.rs            # Module declaration and interface exports for CLI presentation.
 ├── core/                 # Pure Data Models & Business Logic (Domain Layer)
+│   ├── bezel.rs          # Physical monitor geometry and bezel-compensated slicing of spanned images.
 │   ├── config.rs         # Merges defaults, JSON config files, and CLI overrides into a single state.
+│   ├── crop.rs           # Centre and saliency-based (smart) crop window selection.
 │   ├── dimension.rs      # Image geometry logic: parsing, validating, and comparing resolutions.
+│   ├── directory.rs      # Picture directories: scan options, include/exclude globs, weights, collections.
+│   ├── dynamic.rs        # Dynamic wallpaper sets: time or sun keyed frames and their cross-fade.
 │   ├── fileinfo.rs       # Core data structure for image metadata (paths, hashes, sizes, mtime).
+│   ├── fit.rs            # Fit modes (fill, fit, blur, stretch, center, tile) placing a photo in its slot.
+│   ├── frame.rs          # Gutters, outer margin, rounded corners and drop shadows of multi-picture canvases.
+│   ├── history.rs        # Per-cycle wallpaper records (timestamp, monitor, images, effect).
+│   ├── layout.rs         # Picture layouts (strips, grid, hero, justified) dividing a monitor canvas.
+│   ├── matching.rs       # Aspect-ratio-aware assignment of images to monitor slots.
 │   ├── mod.rs            # Module declaration and interface exports for the core domain.
 │   ├── monitors.rs       # Configuration for multi-monitor setups and output-specific settings.
 │   ├── orientation.rs    # Enums and parsing for horizontal/vertical monitor layouts.
-│   └── state.rs          # Manages persistent cache and history to prevent visual duplicates.
+│   ├── output.rs         # Detected physical outputs: name, native mode, position, scale, transform.
+│   ├── profile.rs        # Named profiles overriding directories, filters, effect and interval.
+│   ├── resample.rs       # Photo resampling: filters, linear-light scaling and unsharp mask.
+│   ├── schedule.rs       # Time-of-day and sunrise/sunset rules overriding profile, effect and interval.
+│   ├── state.rs          # Manages persistent cache and history to prevent visual duplicates.
+│   └── sun.rs            # Offline sunrise, sunset and sun position for a configured location.
 ├── effects/              # Sub-package containing all customizable mathematical overlays.
 │   ├── aurora.rs         # Atmospheric Cosmic Aurora wave generator.
+│   ├── base.rs           # Generated canvas bases (solid, gradient, dark) for photo-free wallpapers.
 │   ├── common.rs         # Common mathematical helpers, coordinate viewports, and effect enums.
 │   ├── julia.rs          # Julia Set fractal overlay generation and dynamic fitting.
 │   ├── mandelbrot.rs     # Mandelbrot Set fractal overlay generation and density mapping.
@@ -32,6 +47,7 @@ src/
 │   ├── nova.rs           # Nova Julia liquid fractal overlay generator and fluid dynamics.
 │   └── star.rs           # Cosmic Starfield / Bokeh generator.
 ├── sys/                  # Low-Level System Integration (Input & Data Layer)
+│   ├── control.rs        # Unix-socket control protocol, server and client for the daemon mode.
 │   ├── environment.rs    # Safe access to OS environment variables ($HOME, $SESSION).
 │   ├── metadata.rs       # Image metadata probing and BLAKE3 hashing.
 │   ├── mod.rs            # Module declaration and interface exports for low-level OS operations.
@@ -42,7 +58,7 @@ src/
 │   ├── complex.rs        # Complex number structure, inline arithmetic, and scalar operations.
 │   ├── dependencies.rs   # Pre-flight checks to verify required system binaries are installed.
 │   ├── mod.rs            # Module declaration and interface exports for shared tools.
-│   ├── random.rs         # Seedless randomization and Fisher-Yates shuffling algorithms.
+│   ├── random.rs         # Seedable randomization and Fisher-Yates shuffling algorithms.
 │   └── traits.rs         # Reusable extensions for concurrency and numeric operations.
 ├── app.rs                # Application Heart: Orchestrates the main program flow and run cycles.
 ├── error.rs              # Error Handling: Centralized custom error types and error messages.
@@ -0,0 +1,327 @@
+//! Unix-socket control protocol used by the daemon mode.
+//!
+//! The daemon listens on `$XDG_RUNTIME_DIR/wallswitch.sock` (falling back to the
+//! temporary directory). The protocol is line based and deliberately trivial so it
+//! can also be driven by `socat` or `nc -U` from compositor keybindings:
+//!
+//! ```text
+//! client                          daemon
+//!   | ---- "set-interval 600\n" ---> |
+//!   |                                |  (request forwarded to the main loop)
+//!   | <--- "ok: interval ...\n" ---- |
+//!   |         (connection closed)    |
+//! ```
+//!
+//! Every reply starts with either `ok:` or `error:`.
+
+use crate::{Environment, WallSwitchError, WallSwitchResult};
+use clap::Subcommand;
+use std::{
+    env, fmt,
+    io::{self, BufRead, BufReader, Read, Write},
+    os::unix::{
+        fs::PermissionsExt,
+        net::{UnixListener, UnixStream},
+    },
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    thread,
+    time::Duration,
+};
+
+/// Maximum time a client may take to send its command line.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Commands accepted by the daemon over its control socket.
+#[derive(Debug, Clone, PartialEq, Eq, Subcommand)]
+pub enum ControlCommand {
+    /// Switch to a new set of wallpapers immediately.
+    Next,
+    /// Re-apply an earlier set of wallpapers from the history.
+    Previous {
+        /// Number of cycles to go back from the displayed set (0 re-applies it).
+        #[arg(default_value_t = 1)]
+        steps: usize,
+    },
+    /// Stop the automatic timer (manual `next`/`previous` still work).
+    Pause,
+    /// Restart the automatic timer.
+    Resume,
+    /// Print the daemon state, interval and current wallpapers.
+    Status,
+    /// Re-read the configuration file from disk.
+    ReloadConfig,
+    /// Change the interval (in seconds) between automatic switches.
+    SetInterval {
+        /// New interval in seconds (at least 5).
+        seconds: u64,
+    },
+    /// Switch to a named profile of the configuration file.
+    Profile {
+        /// Profile name (omit it to go back to the base settings).
+        name: Option<String>,
+    },
+}
+
+impl fmt::Display for ControlCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Next => write!(f, "next"),
+            Self::Previous { steps } => write!(f, "previous {steps}"),
+            Self::Pause => write!(f, "pause"),
+            Self::Resume => write!(f, "resume"),
+            Self::Status => write!(f, "status"),
+            Self::ReloadConfig => write!(f, "reload-config"),
+            Self::SetInterval { seconds } => write!(f, "set-interval {seconds}"),
+            Self::Profile { name: None } => write!(f, "profile"),
+            Self::Profile { name: Some(name) } => write!(f, "profile {name}"),
+        }
+    }
+}
+
+impl FromStr for ControlCommand {
+    type Err = WallSwitchError;
+
+    fn from_str(s: &str) -> WallSwitchResult<Self> {
+        let mut words = s.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let argument = words.next();
+
+        let invalid = || WallSwitchError::InvalidValue {
+            arg: "command".to_string(),
+            value: s.trim().to_string(),
+        };
+
+        if words.next().is_some() {
+            return Err(invalid());
+        }
+
+        let command = match (name, argument) {
+            ("next", None) => Self::Next,
+            ("previous", None) => Self::Previous { steps: 1 },
+            ("previous", Some(value)) => Self::Previous {
+                steps: value.parse().map_err(|_| invalid())?,
+            },
+            ("pause", None) => Self::Pause,
+            ("resume", None) => Self::Resume,
+            ("status", None) => Self::Status,
+            ("reload-config", None) => Self::ReloadConfig,
+            ("set-interval", Some(value)) => Self::SetInterval {
+                seconds: value.parse().map_err(|_| invalid())?,
+            },
+            ("profile", name) => Self::Profile {
+                name: name.map(String::from),
+            },
+            _ => return Err(invalid()),
+        };
+
+        Ok(command)
+    }
+}
+
+/// A command received from a client, waiting for the daemon's reply.
+pub struct ControlRequest {
+    pub command: ControlCommand,
+    reply: Sender<String>,
+}
+
+impl ControlRequest {
+    /// Sends a successful reply back to the waiting client.
+    pub fn ok(self, message: impl fmt::Display) {
+        let _ = self.reply.send(format!("ok: {message}"));
+    }
+
+    /// Sends an error reply back to the waiting client.
+    pub fn error(self, message: impl fmt::Display) {
+        let _ = self.reply.send(format!("error: {message}"));
+    }
+}
+
+/// Listening side of the control socket.
+///
+/// Connections are accepted on a background thread and forwarded to the owner
+/// through a channel, so the main loop stays single-threaded with respect to
+/// `Config` and `State`. The socket file is removed when the server is dropped.
+pub struct ControlServer {
+    path: PathBuf,
+    receiver: Receiver<ControlRequest>,
+}
+
+impl ControlServer {
+    /// Binds the control socket and starts accepting clients in the background.
+    ///
+    /// A leftover socket file from a previous (killed) daemon is replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WallSwitchError::IOError`] if the socket cannot be created.
+    pub fn bind(env: &Environment) -> WallSwitchResult<Self> {
+        let path = get_socket_path(env);
+        let io_error = |io_error| WallSwitchError::IOError {
+            path: path.clone(),
+            io_error,
+        };
+
+        if path.exists() {
+            std::fs::remove_file(&path).map_err(io_error)?;
+        }
+
+        let listener = UnixListener::bind(&path).map_err(io_error)?;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+            .map_err(io_error)?;
+
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                handle_client(stream, &sender);
+            }
+        });
+
+        Ok(ControlServer { path, receiver })
+    }
+
+    /// Returns the path of the bound socket.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Waits for the next client request.
+    ///
+    /// With `Some(timeout)` this returns `Ok(None)` once the timeout elapses; with
+    /// `None` it blocks until a request arrives.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WallSwitchError::Io`] if the listener thread has stopped, since no
+    /// request can arrive anymore (a paused daemon would otherwise spin forever).
+    pub fn wait(&self, timeout: Option<Duration>) -> WallSwitchResult<Option<ControlRequest>> {
+        let request = match timeout {
+            Some(timeout) => match self.receiver.recv_timeout(timeout) {
+                Ok(request) => request,
+                Err(RecvTimeoutError::Timeout) => return Ok(None),
+                Err(RecvTimeoutError::Disconnected) => return Err(self.disconnected()),
+            },
+            None => self.receiver.recv().map_err(|_| self.disconnected())?,
+        };
+
+        Ok(Some(request))
+    }
+
+    /// Error reported once the listener thread is gone.
+    fn disconnected(&self) -> WallSwitchError {
+        WallSwitchError::Io(io::Error::other(format!(
+            "control socket listener {:?} stopped",
+            self.path
+        )))
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Reads one command line from the client, forwards it and writes the reply.
+fn handle_client(mut stream: UnixStream, sender: &Sender<ControlRequest>) {
+    let _ = stream.set_read_timeout(Some(CLIENT_TIMEOUT));
+
+    let mut line = String::new();
+    let reply = match BufReader::new(&stream).read_line(&mut line) {
+        Err(err) => format!("error: {err}"),
+        Ok(_) => match line.parse::<ControlCommand>() {
+            Err(err) => format!("error: {err}"),
+            Ok(command) => {
+                let (reply, response) = mpsc::channel();
+                if sender.send(ControlRequest { command, reply }).is_err() {
+                    return;
+                }
+                response
+                    .recv()
+                    .unwrap_or_else(|_| "error: daemon stopped".to_string())
+            }
+        },
+    };
+
+    let _ = writeln!(stream, "{reply}");
+}
+
+/// Sends a command to the running daemon and returns its reply.
+///
+/// # Errors
+///
+/// Returns [`WallSwitchError::DaemonError`] if no daemon is listening, or
+/// [`WallSwitchError::DaemonReply`] if the daemon answers with an `error:` reply.
+pub fn send_command(command: &ControlCommand, env: &Environment) -> WallSwitchResult<String> {
+    let daemon_error = |e: std::io::Error| {
+        WallSwitchError::DaemonError(env.get_pkg_name().to_string(), e.to_string())
+    };
+
+    let mut stream = UnixStream::connect(get_socket_path(env)).map_err(daemon_error)?;
+    writeln!(stream, "{command}").map_err(daemon_error)?;
+
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply).map_err(daemon_error)?;
+    let reply = reply.trim_end().to_string();
+
+    match reply.strip_prefix("error: ") {
+        Some(message) => Err(WallSwitchError::DaemonReply(message.to_string())),
+        None => Ok(reply),
+    }
+}
+
+/// Returns `$XDG_RUNTIME_DIR/<pkg_name>.sock`, or the same name in the temp dir.
+pub fn get_socket_path(env: &Environment) -> PathBuf {
+    let runtime_dir = env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| env.get_temp_dir().to_path_buf());
+
+    runtime_dir.join(format!("{}.sock", env.get_pkg_name()))
+}
+
+//----------------------------------------------------------------------------//
+//                                   Tests                                    //
+//----------------------------------------------------------------------------//
+
+#[cfg(test)]
+mod tests_control {
+    use super::*;
+
+    #[test]
+    fn test_command_round_trip() {
+        let commands = [
+            ControlCommand::Next,
+            ControlCommand::Previous { steps: 2 },
+            ControlCommand::Pause,
+            ControlCommand::Resume,
+            ControlCommand::Status,
+            ControlCommand::ReloadConfig,
+            ControlCommand::SetInterval { seconds: 600 },
+            ControlCommand::Profile {
+                name: Some("night".to_string()),
+            },
+            ControlCommand::Profile { name: None },
+        ];
+
+        for command in commands {
+            let line = command.to_string();
+            assert_eq!(line.parse::<ControlCommand>().unwrap(), command);
+        }
+
+        assert_eq!(
+            " set-interval   90 \n".parse::<ControlCommand>().unwrap(),
+            ControlCommand::SetInterval { seconds: 90 }
+        );
+        assert_eq!(
+            "previous".parse::<ControlCommand>().unwrap(),
+            ControlCommand::Previous { steps: 1 }
+        );
+        assert!("set-interval".parse::<ControlCommand>().is_err());
+        assert!("set-interval soon".parse::<ControlCommand>().is_err());
+        assert!("next now".parse::<ControlCommand>().is_err());
+        assert!("shutdown".parse::<ControlCommand>().is_err());
+        assert!("profile work night".parse::<ControlCommand>().is_err());
+    }
+}
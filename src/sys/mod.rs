@@ -1,6 +1,7 @@
+mod control;
 mod environment;
 mod metadata;
 mod pids;
 mod walkdir;
 
-pub use self::{environment::*, metadata::*, pids::*, walkdir::*};
+pub use self::{control::*, environment::*, metadata::*, pids::*, walkdir::*};
@@ -5,19 +5,21 @@
 //! It leverages `walkdir` for robust handling of file system edge cases compared
 //! to manual `std::fs` recursion.
 
-use crate::{Config, FileInfo, WallSwitchResult};
-use std::path::Path;
+use crate::{Config, Directory, FileInfo, WallSwitchResult};
 use walkdir::WalkDir;
 
-/// Retrieves and filters file information from a specified directory.
+/// Retrieves and filters file information from a configured image directory.
 ///
-/// This function traverses the directory recursively, filtering files by the extensions
-/// specified in the provided [`Config`]. It extracts metadata such as file size and
-/// modification time, returning a list of [`FileInfo`] structs.
+/// This function traverses the directory according to its options (recursion, maximum
+/// depth, symbolic links, hidden entries and include/exclude globs), filtering files by
+/// the extensions specified in the provided [`Config`]. It extracts metadata such as
+/// file size and modification time, returning a list of [`FileInfo`] structs.
+///
+/// Excluded and hidden subdirectories are not descended into at all.
 ///
 /// # Arguments
 ///
-/// * `path` - A path reference to the directory to be searched.
+/// * `directory` - The directory to be searched, with its scanning options.
 /// * `config` - A reference to the configuration containing the allowed file extensions.
 ///
 /// # Errors
@@ -29,21 +31,31 @@ use walkdir::WalkDir;
 /// # Examples
 ///
 /// ```ignore
-/// use wallswitch::{get_files_from_directory, Config};
-/// use std::path::Path;
+/// use wallswitch::{get_files_from_directory, Config, Directory};
 ///
 /// let config = Config { extensions: vec!["jpg".to_string(), "png".to_string()] };
-/// let files = get_files_from_directory(Path::new("./images"), &config)?;
+/// let files = get_files_from_directory(&Directory::new("./images"), &config)?;
 /// ```
-pub fn get_files_from_directory<P>(path: P, config: &Config) -> WallSwitchResult<Vec<FileInfo>>
-where
-    P: AsRef<Path>,
-{
+pub fn get_files_from_directory(
+    directory: &Directory,
+    config: &Config,
+) -> WallSwitchResult<Vec<FileInfo>> {
     let extensions = &config.extensions;
+    let root = &directory.path;
 
-    let mut all_files: Vec<FileInfo> = WalkDir::new(path)
-        .follow_links(false)
+    let mut all_files: Vec<FileInfo> = WalkDir::new(root)
+        .follow_links(directory.follow_links)
+        .max_depth(directory.depth_limit())
         .into_iter()
+        // Skip excluded and hidden subdirectories without descending into them
+        .filter_entry(|entry| {
+            entry.depth() == 0
+                || !entry.file_type().is_dir()
+                || entry
+                    .path()
+                    .strip_prefix(root)
+                    .is_ok_and(|relative| !directory.prunes(relative))
+        })
         // Safely ignore directory traversal errors
         .filter_map(Result::ok)
         // Ensure the entry is a file
@@ -60,6 +72,13 @@ where
                         .any(|allowed_ext| ext_str.eq_ignore_ascii_case(allowed_ext))
                 })
         })
+        // Apply the include/exclude globs of the directory
+        .filter(|entry| {
+            entry
+                .path()
+                .strip_prefix(root)
+                .is_ok_and(|relative| directory.accepts(relative))
+        })
         // Map the DirEntry to FileInfo, ignoring entries with inaccessible metadata
         .filter_map(|entry| {
             let metadata = entry.metadata().ok()?;
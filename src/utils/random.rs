@@ -1,5 +1,16 @@
 use crate::{WallSwitchError, WallSwitchResult};
-use std::hash::{BuildHasher, Hasher, RandomState};
+use std::{
+    cell::Cell,
+    hash::{BuildHasher, Hasher, RandomState},
+};
+
+/// Increment of the SplitMix64 sequence (the 64-bit golden ratio).
+const SPLITMIX_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+thread_local! {
+    /// State of the seeded generator active on this thread (`None` means unseeded).
+    static SEEDED_STATE: Cell<Option<u64>> = const { Cell::new(None) };
+}
 
 /// Trait to extend slices with shuffling and random selection capabilities.
 pub trait RandomExt {
@@ -123,12 +134,74 @@ impl<T> RandomExt for [T] {
     }
 }
 
+// --- Seeded Generator --- //
+
+/// SplitMix64 output function: scrambles a 64-bit state into a well-distributed value.
+///
+/// See: <https://prng.di.unimi.it/splitmix64.c>
+#[inline]
+fn splitmix64(state: u64) -> u64 {
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Restores the previous generator state of the thread when dropped (even on panic).
+struct SeedGuard(Option<u64>);
+
+impl Drop for SeedGuard {
+    fn drop(&mut self) {
+        SEEDED_STATE.set(self.0);
+    }
+}
+
+/// Runs `f` with every random draw on the current thread driven by `seed`.
+///
+/// All helpers of this module ([`rand`], [`get_random_integer`], [`RandomExt`])
+/// become deterministic inside the closure. The state is thread-local, so work
+/// spread over Rayon threads must call `with_seed` again (see [`derive_seed`]).
+///
+/// # Examples
+///
+/// ```
+/// use wallswitch::{RandomExt, with_seed};
+///
+/// let shuffled = |seed| {
+///     with_seed(seed, || {
+///         let mut v: Vec<u32> = (1..=20).collect();
+///         v.shuffle();
+///         v
+///     })
+/// };
+/// assert_eq!(shuffled(42), shuffled(42));
+/// ```
+pub fn with_seed<T>(seed: u64, f: impl FnOnce() -> T) -> T {
+    let _guard = SeedGuard(SEEDED_STATE.replace(Some(seed)));
+    f()
+}
+
+/// Derives an independent seed for sub-task `stream` (e.g. a monitor index) of `seed`.
+pub fn derive_seed(seed: u64, stream: u64) -> u64 {
+    splitmix64(seed ^ splitmix64(stream.wrapping_add(SPLITMIX_GAMMA)))
+}
+
 // --- Rand (Unificado) --- //
 
-/// Helper function to generate a raw 64-bit unsigned integer using the system's `RandomState`.
+/// Helper function to generate a raw 64-bit unsigned integer.
+///
+/// Draws from the seeded generator of the thread if one is active (see [`with_seed`]),
+/// otherwise from the system's `RandomState`.
 #[inline]
 fn raw_u64() -> u64 {
-    RandomState::new().build_hasher().finish()
+    SEEDED_STATE.with(|state| match state.get() {
+        Some(current) => {
+            let next = current.wrapping_add(SPLITMIX_GAMMA);
+            state.set(Some(next));
+            splitmix64(next)
+        }
+        None => RandomState::new().build_hasher().finish(),
+    })
 }
 
 /// A trait for types that can be generated randomly.
@@ -275,6 +348,50 @@ where
     }
 }
 
+/// Returns true with a probability of `percent` (0 to 100) percent.
+///
+/// No random number is drawn for 0 or 100, so certain outcomes leave the
+/// seeded stream untouched.
+pub fn percent_chance(percent: u8) -> bool {
+    match percent {
+        0 => false,
+        100.. => true,
+        _ => get_random_integer::<u8, u8>(1, 100) <= percent,
+    }
+}
+
+/// Picks an index with a probability proportional to its weight.
+///
+/// Returns `None` if the slice is empty or every weight is zero.
+///
+/// # Examples
+///
+/// ```
+/// use wallswitch::get_weighted_index;
+///
+/// assert_eq!(get_weighted_index(&[0, 5, 0]), Some(1));
+/// assert_eq!(get_weighted_index(&[0, 0]), None);
+/// ```
+pub fn get_weighted_index(weights: &[u64]) -> Option<usize> {
+    let total = weights
+        .iter()
+        .try_fold(0u64, |sum, &weight| sum.checked_add(weight))?;
+
+    if total == 0 {
+        return None;
+    }
+
+    let mut target: u64 = get_random_integer(0, total - 1);
+    weights.iter().position(|&weight| {
+        if target < weight {
+            true
+        } else {
+            target -= weight;
+            false
+        }
+    })
+}
+
 //----------------------------------------------------------------------------//
 //                                   Tests                                    //
 //----------------------------------------------------------------------------//
@@ -338,6 +455,47 @@ mod tests_random {
         assert!(invalid.is_err());
     }
 
+    #[test]
+    fn test_seeded_draws_are_reproducible() {
+        let draw = |seed| {
+            with_seed(seed, || {
+                let mut data: Vec<usize> = (0..50).collect();
+                data.shuffle();
+                let value: u32 = get_random_integer(0, 1_000_000);
+                (data, value, rand::<f64>())
+            })
+        };
+
+        assert_eq!(draw(7), draw(7));
+        assert_ne!(draw(7), draw(8));
+        assert_ne!(derive_seed(7, 0), derive_seed(7, 1));
+
+        // Nested scopes restore the outer stream afterwards
+        let outer = with_seed(1, || {
+            let first: u64 = rand();
+            let _ = with_seed(2, rand::<u64>);
+            (first, rand::<u64>())
+        });
+        let plain = with_seed(1, || (rand::<u64>(), rand::<u64>()));
+        assert_eq!(outer, plain);
+    }
+
+    #[test]
+    fn test_percent_chance_and_weights() {
+        assert!(percent_chance(100));
+        assert!(!percent_chance(0));
+
+        let mut counts = [0usize; 3];
+        with_seed(11, || {
+            for _ in 0..3_000 {
+                counts[get_weighted_index(&[1, 0, 2]).unwrap()] += 1;
+            }
+        });
+
+        assert_eq!(counts[1], 0);
+        assert!(counts[2] > counts[0], "weights ignored: {counts:?}");
+    }
+
     #[test]
     fn test_get_random_sample_success() {
         let data = [42, 100, 200];